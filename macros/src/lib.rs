@@ -1,7 +1,10 @@
 use proc_macro::TokenStream;
 use proc_macro2::Span;
 use quote::quote;
-use syn::{parse::Parse, parse_macro_input, token::Bracket, Expr, Ident, Lit, Token, Type};
+use syn::{
+    parse::Parse, parse_macro_input, token::Bracket, Attribute, Data, DeriveInput, Expr, Fields,
+    Ident, Lit, LitStr, Token, Type,
+};
 
 enum IdentOrExpr {
     Ident(Ident),
@@ -15,10 +18,13 @@ struct FunctionsInput {
 }
 
 struct ObjectInput {
+    docs: Vec<Attribute>,
     r#struct: Ident,
     name: Option<Lit>,
     functions: Option<FunctionsInput>,
     class: Option<Lit>,
+    singleton: Option<Ident>,
+    deprecated: Option<LitStr>,
     impls: Vec<Ident>,
 }
 
@@ -59,13 +65,25 @@ impl Parse for FunctionsInput {
 
 impl Parse for ObjectInput {
     fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let docs = input.call(Attribute::parse_outer)?;
+
+        if let Some(attr) = docs.iter().find(|attr| !attr.path().is_ident("doc")) {
+            return Err(syn::Error::new_spanned(
+                attr,
+                "only doc comments are allowed before the struct name",
+            ));
+        }
+
         let r#struct = input.parse()?;
 
         let mut result = ObjectInput {
+            docs,
             r#struct,
             name: None,
             functions: None,
             class: None,
+            singleton: None,
+            deprecated: None,
             impls: vec![],
         };
 
@@ -109,6 +127,24 @@ impl Parse for ObjectInput {
 
                 let class = content.parse::<Lit>()?;
 
+                let Lit::Str(path) = &class else {
+                    return Err(syn::Error::new(
+                        class.span(),
+                        "@class expects a string literal",
+                    ));
+                };
+
+                if !is_valid_class_path(&path.value()) {
+                    return Err(syn::Error::new(
+                        path.span(),
+                        format!(
+                            "invalid class path {:?}, expected a path like \"Class /Script/Engine.Actor\" \
+                             (the class of the object, followed by `/<Mount>/<Path>.<Name>`)",
+                            path.value()
+                        ),
+                    ));
+                }
+
                 result.class = Some(class);
             } else if name == "impls" {
                 let content;
@@ -117,13 +153,75 @@ impl Parse for ObjectInput {
                 let impls = content.parse_terminated(Ident::parse, Token![,])?;
 
                 result.impls = impls.into_iter().collect();
+            } else if name == "singleton" {
+                result.singleton = Some(name);
+            } else if name == "deprecated" {
+                let content;
+                syn::parenthesized!(content in input);
+
+                let note = content.parse::<Lit>()?;
+
+                let Lit::Str(note) = note else {
+                    return Err(syn::Error::new(
+                        note.span(),
+                        "@deprecated expects a string literal",
+                    ));
+                };
+
+                if result.deprecated.is_some() {
+                    return Err(syn::Error::new(
+                        name.span(),
+                        "@deprecated can only be given once",
+                    ));
+                }
+
+                result.deprecated = Some(note);
+            } else if name == "doc" {
+                let content;
+                syn::parenthesized!(content in input);
+
+                let doc = content.parse::<LitStr>()?;
+
+                result.docs.push(syn::parse_quote! { #[doc = #doc] });
             }
         }
 
+        if let (Some(singleton), None) = (&result.singleton, &result.class) {
+            return Err(syn::Error::new(
+                singleton.span(),
+                "@singleton requires the class path to be given using @class",
+            ));
+        }
+
         Ok(result)
     }
 }
 
+/// Checks that `path` looks like `<Class> /<Mount>/<Path>.<Name>`, e.g. `Class /Script/Engine.Actor` or
+/// `BlueprintGeneratedClass /Game/Blueprints/BP_Player.BP_Player_C`
+fn is_valid_class_path(path: &str) -> bool {
+    let is_identifier = |value: &str| {
+        value.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+            && value.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+    };
+    let is_segment = |value: &str| {
+        !value.is_empty()
+            && value
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+    };
+
+    let Some((kind, path)) = path.split_once(' ') else {
+        return false;
+    };
+
+    let Some((package, name)) = path.strip_prefix('/').and_then(|path| path.split_once('.')) else {
+        return false;
+    };
+
+    is_identifier(kind) && package.split('/').all(is_segment) && is_identifier(name)
+}
+
 /// A procedural macro that defines a struct, its associated methods, and additional functionality
 /// for integrating with the `rusty_uevr` and Unreal Engine API. This macro can generate the necessary
 /// code to support features like pointer conversions, function bindings, class associations,
@@ -133,11 +231,21 @@ impl Parse for ObjectInput {
 ///
 /// The `define_object!` macro allows you to define a new struct with various optional attributes:
 ///
+/// - **Documentation**: Doc comments placed before the struct name, or given using `@doc("...")`,
+///   are added to the generated struct.
 /// - **Struct name**: The first argument is the name of the struct that will be defined.
 /// - **Static name**: Optionally, you can specify a static name for the object using a string literal.
 /// - **Functions**: You can specify function bindings using `@functions` which will set up the handle,
 ///   function list, and the associated SDk field for the object.
 /// - **Class association**: Using `@class`, you can associate the struct with a specific Unreal class object.
+///   When omitted, types implementing `RUObject` look the class up in `/Script/CoreUObject` and `/Script/Engine`
+///   using the static name.
+///   The path is checked at compile time to look like `<Class> /<Mount>/<Path>.<Name>`, e.g.
+///   `ScriptStruct /Script/CoreUObject.Vector` or `BlueprintGeneratedClass /Game/BP_Player.BP_Player_C`.
+/// - **Singleton**: `@singleton` adds `get_singleton`, which returns the first non-default object of the
+///   class. Requires `@class`.
+/// - **Deprecation**: `@deprecated("use NewType instead")` marks the generated struct as deprecated,
+///   e.g. for types that were renamed in newer engine versions.
 /// - **Trait implementations**: You can implement traits for the object using `@impls`.
 ///
 /// The macro generates the following for each object:
@@ -145,12 +253,12 @@ impl Parse for ObjectInput {
 /// - Implementation of the `rusty_uevr::api::Ptr` trait for converting the struct to/from a raw pointer.
 /// - Optional methods such as `internal_name` and `to_handle`/`from_handle` for easier interaction with the engine.
 /// - Static variable for function bindings and initialization via the `initialize` method.
-/// - Static class association for UObject discovery.
+/// - Static class association for UObject discovery, cached per type after the first lookup.
 /// - Optionally, user-defined trait implementations for the struct.
 ///
 /// ### Example Usage
 ///
-/// ```rust
+/// ```ignore
 /// define_object!(
 ///     UEnum,
 /// );
@@ -178,28 +286,45 @@ impl Parse for ObjectInput {
 /// );
 ///
 /// define_object!(
+///     /// A struct type defined in a script package
 ///     UScriptStruct,
 ///     "ScriptStruct",
 ///     @functions(UEVR_UScriptStructHandle, UEVR_UScriptStructFunctions, uscriptstruct),
 ///     @class("Class /Script/CoreUObject.ScriptStruct"),
 ///     @impls(RUObject, RUField, RUStruct)
 /// );
+///
+/// define_object!(
+///     UGameInstance,
+///     "GameInstance",
+///     @class("Class /Script/Engine.GameInstance"),
+///     @singleton,
+///     @impls(RUObject)
+/// );
 /// ```
 #[proc_macro]
 pub fn define_object(input: TokenStream) -> TokenStream {
     let ObjectInput {
+        docs,
         r#struct,
         name,
         functions,
         class,
+        singleton,
+        deprecated,
         impls,
     } = parse_macro_input!(input);
 
+    let deprecated = deprecated.map(|note| quote! { #[deprecated(note = #note)] });
+
     let mut fragments = vec![quote! {
+        #(#docs)*
+        #deprecated
         #[derive(Clone, Copy)]
         pub struct #r#struct(*mut std::ffi::c_void);
 
         #[automatically_derived]
+        #[allow(deprecated)]
         impl rusty_uevr::api::Ptr for #r#struct {
             fn from_ptr(ptr: *mut std::ffi::c_void) -> Self {
                 Self(ptr)
@@ -211,9 +336,10 @@ pub fn define_object(input: TokenStream) -> TokenStream {
         }
     }];
 
-    if let Some(name) = name {
+    if let Some(name) = &name {
         fragments.push(quote! {
             #[automatically_derived]
+            #[allow(deprecated)]
             impl #r#struct {
                 pub const fn internal_name() -> &'static str {
                     #name
@@ -237,9 +363,11 @@ pub fn define_object(input: TokenStream) -> TokenStream {
         fragments.push(quote! {
             #[doc(hidden)]
             #[allow(non_upper_case_globals)]
-            static mut #global: *const rusty_uevr::bindings::#functions = std::ptr::null();
+            static #global: std::sync::atomic::AtomicPtr<rusty_uevr::bindings::#functions> =
+                std::sync::atomic::AtomicPtr::new(std::ptr::null_mut());
 
             #[automatically_derived]
+            #[allow(deprecated)]
             impl #r#struct {
                 pub fn to_handle(&self) -> rusty_uevr::bindings::#handle {
                     self.to_ptr() as rusty_uevr::bindings::#handle
@@ -258,13 +386,29 @@ pub fn define_object(input: TokenStream) -> TokenStream {
                 }
 
                 fn initialize<'a>() -> &'a rusty_uevr::bindings::#functions {
-                    unsafe {
-                        if #global.is_null() {
-                            #global = #field;
-                        }
+                    unsafe { &*rusty_uevr::api::load_function_table(&#global, || #field) }
+                }
+            }
+        });
+    }
 
-                        &*#global
-                    }
+    let cache = Ident::new(&format!("__{}_class", r#struct), Span::call_site());
+    let cache_static = quote! {
+        #[doc(hidden)]
+        #[allow(non_upper_case_globals)]
+        static #cache: std::sync::atomic::AtomicPtr<std::ffi::c_void> =
+            std::sync::atomic::AtomicPtr::new(std::ptr::null_mut());
+    };
+
+    if singleton.is_some() {
+        fragments.push(quote! {
+            #[automatically_derived]
+            #[allow(deprecated)]
+            impl #r#struct {
+                /// Returns the first object of this class, ignoring the default object
+                pub fn get_singleton() -> Option<Self> {
+                    <Self as rusty_uevr::api::StaticClass>::static_class_safe()?
+                        .get_first_object_matching::<Self>(false)
                 }
             }
         });
@@ -272,9 +416,49 @@ pub fn define_object(input: TokenStream) -> TokenStream {
 
     if let Some(class) = class {
         fragments.push(quote! {
+            #cache_static
+
+            #[allow(deprecated)]
+            impl rusty_uevr::api::StaticClass for #r#struct {
+                fn static_class_safe() -> Option<rusty_uevr::api::UClass> {
+                    rusty_uevr::api::find_static_class_cached(&#cache, #class)
+                }
+            }
+        });
+    } else if let Some(name) = name.filter(|_| impls.iter().any(|i| i == "RUObject")) {
+        // Without an explicit class path, guess the path from the most common script packages. Types
+        // that aren't objects have no class to find.
+        let warned = Ident::new(&format!("__{}_class_warned", r#struct), Span::call_site());
+
+        fragments.push(quote! {
+            #cache_static
+
+            #[doc(hidden)]
+            #[allow(non_upper_case_globals)]
+            static #warned: std::sync::atomic::AtomicBool = std::sync::atomic::AtomicBool::new(false);
+
+            #[allow(deprecated)]
             impl rusty_uevr::api::StaticClass for #r#struct {
                 fn static_class_safe() -> Option<rusty_uevr::api::UClass> {
-                    rusty_uevr::api::API::get().find_uobject(#class)
+                    let class = rusty_uevr::api::find_static_class_cached(
+                        &#cache,
+                        concat!("Class /Script/CoreUObject.", #name),
+                    )
+                    .or_else(|| {
+                        rusty_uevr::api::find_static_class_cached(
+                            &#cache,
+                            concat!("Class /Script/Engine.", #name),
+                        )
+                    });
+
+                    if class.is_none() && !#warned.swap(true, std::sync::atomic::Ordering::Relaxed) {
+                        rusty_uevr::warn!(
+                            "Could not find the class for {}, specify its path using @class",
+                            #name
+                        );
+                    }
+
+                    class
                 }
             }
         });
@@ -284,6 +468,7 @@ pub fn define_object(input: TokenStream) -> TokenStream {
         fragments.push(quote! {
             #(
                 #[automatically_derived]
+                #[allow(deprecated)]
                 impl #impls for #r#struct {}
             )*
         });
@@ -299,3 +484,167 @@ pub fn define_object(input: TokenStream) -> TokenStream {
         })
         .into()
 }
+
+/// Registers a type defined using `define_object!` in `rusty_uevr::api::registry`, so its class can be
+/// looked up by the static name using `registry::find_class_by_name`
+///
+/// Requires the `registry` feature of `rusty_uevr`, and a static name on the object.
+///
+/// ```ignore
+/// #[static_class]
+/// define_object!(
+///     APlayerController,
+///     "PlayerController",
+///     @impls(RUObject)
+/// );
+/// ```
+#[proc_macro_attribute]
+pub fn static_class(_attr: TokenStream, item: TokenStream) -> TokenStream {
+    let item = parse_macro_input!(item as syn::ItemMacro);
+
+    let r#struct = match item.mac.parse_body_with(|input: syn::parse::ParseStream| {
+        input.call(Attribute::parse_outer)?;
+
+        let r#struct = input.parse::<Ident>()?;
+        input.parse::<proc_macro2::TokenStream>()?;
+
+        Ok(r#struct)
+    }) {
+        Ok(r#struct) => r#struct,
+        Err(error) => return error.to_compile_error().into(),
+    };
+
+    let registration = Ident::new(&format!("__{}_registration", r#struct), Span::call_site());
+
+    quote! {
+        #item
+
+        #[doc(hidden)]
+        #[allow(non_upper_case_globals, deprecated)]
+        #[rusty_uevr::__linkme::distributed_slice(rusty_uevr::api::registry::STATIC_CLASSES)]
+        #[linkme(crate = rusty_uevr::__linkme)]
+        static #registration: rusty_uevr::api::registry::StaticClassEntry =
+            rusty_uevr::api::registry::StaticClassEntry {
+                name: #r#struct::internal_name(),
+                static_class: <#r#struct as rusty_uevr::api::StaticClass>::static_class_safe,
+            };
+    }
+    .into()
+}
+
+/// Implements `rusty_uevr::api::Ptr` for a `#[repr(transparent)]` struct with a single `*mut c_void` or
+/// `*const c_void` field, the same way `define_object!` does
+///
+/// ```ignore
+/// #[derive(Clone, Copy, Ptr)]
+/// #[repr(transparent)]
+/// pub struct UMyPluginSubsystem(*mut std::ffi::c_void);
+/// ```
+#[proc_macro_derive(Ptr)]
+pub fn derive_ptr(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    match expand_ptr(&input) {
+        Ok(tokens) => tokens.into(),
+        Err(error) => error.to_compile_error().into(),
+    }
+}
+
+fn expand_ptr(input: &DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let is_transparent = input.attrs.iter().any(|attr| {
+        attr.path().is_ident("repr")
+            && attr
+                .parse_args::<Ident>()
+                .is_ok_and(|repr| repr == "transparent")
+    });
+
+    if !is_transparent {
+        return Err(syn::Error::new(
+            input.ident.span(),
+            "Ptr can only be derived for #[repr(transparent)] structs",
+        ));
+    }
+
+    let Data::Struct(data) = &input.data else {
+        return Err(syn::Error::new(
+            input.ident.span(),
+            "Ptr can only be derived for structs",
+        ));
+    };
+
+    let field = match &data.fields {
+        Fields::Named(fields) if fields.named.len() == 1 => &fields.named[0],
+        Fields::Unnamed(fields) if fields.unnamed.len() == 1 => &fields.unnamed[0],
+        fields => {
+            return Err(syn::Error::new_spanned(
+                fields,
+                "Ptr can only be derived for structs with a single field",
+            ))
+        }
+    };
+
+    let is_c_void_ptr = match &field.ty {
+        Type::Ptr(ptr) => match &*ptr.elem {
+            Type::Path(path) => path
+                .path
+                .segments
+                .last()
+                .is_some_and(|segment| segment.ident == "c_void"),
+            _ => false,
+        },
+        _ => false,
+    };
+
+    if !is_c_void_ptr {
+        return Err(syn::Error::new_spanned(
+            &field.ty,
+            "expected a `*mut c_void` or `*const c_void` field",
+        ));
+    }
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let constructor = match &field.ident {
+        Some(ident) => quote! { Self { #ident: ptr as _ } },
+        None => quote! { Self(ptr as _) },
+    };
+    let member = match &field.ident {
+        Some(ident) => quote! { #ident },
+        None => quote! { 0 },
+    };
+
+    Ok(quote! {
+        #[automatically_derived]
+        impl #impl_generics rusty_uevr::api::Ptr for #name #ty_generics #where_clause {
+            fn from_ptr(ptr: *mut std::ffi::c_void) -> Self {
+                #constructor
+            }
+
+            fn to_ptr(&self) -> *mut std::ffi::c_void {
+                self.#member as *mut std::ffi::c_void
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn class_paths() {
+        assert!(is_valid_class_path("Class /Script/Engine.Actor"));
+        assert!(is_valid_class_path(
+            "ScriptStruct /Script/CoreUObject.Vector"
+        ));
+        assert!(is_valid_class_path(
+            "BlueprintGeneratedClass /Game/Blueprints/BP_Player.BP_Player_C"
+        ));
+
+        assert!(!is_valid_class_path("/Script/Engine.Actor"));
+        assert!(!is_valid_class_path("Class Engine.Actor"));
+        assert!(!is_valid_class_path("Class /Script/Engine"));
+        assert!(!is_valid_class_path("Class /Script//Engine.Actor"));
+        assert!(!is_valid_class_path("Class /Script/Engine.Actor Extra"));
+    }
+}
@@ -0,0 +1,209 @@
+use crate::{
+    api::{
+        FLinearColor, FProperty, FString, FStructProperty, Ptr, RFProperty, RUObject, RUStruct,
+        UClass, UEngine, UFunction, UObject, API,
+    },
+    bindings::UEVR_FCanvasHandle,
+};
+
+use std::{
+    collections::HashMap,
+    ffi::c_void,
+    mem::size_of,
+    sync::{LazyLock, Mutex},
+};
+
+/// UCanvas objects found for an FCanvas, keyed by the pointer of the FCanvas
+static CANVAS_OBJECTS: LazyLock<Mutex<HashMap<usize, usize>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// `EBlendMode::BLEND_Translucent`
+const BLEND_TRANSLUCENT: u8 = 2;
+
+/// Canvas the viewport client draws to, passed to the viewport client draw callbacks
+///
+/// UEVR passes the native FCanvas, while the drawing functions live on the UCanvas object that wraps it.
+/// That object is found by looking for a UCanvas which points to this FCanvas, so drawing does nothing
+/// until the game has set one up.
+#[derive(Clone, Copy)]
+pub struct Canvas(UEVR_FCanvasHandle);
+
+impl Canvas {
+    pub fn new(handle: UEVR_FCanvasHandle) -> Self {
+        Self(handle)
+    }
+
+    pub fn raw(&self) -> UEVR_FCanvasHandle {
+        self.0
+    }
+
+    /// Draws text using the small font of the engine
+    pub fn draw_text(&self, text: &str, x: f32, y: f32, color: FLinearColor, scale: f32) {
+        let Some(font) = UEngine::get().read_object_property("SmallFont") else {
+            return;
+        };
+
+        let Some(mut params) = self.params("K2_DrawText") else {
+            return;
+        };
+
+        params.write("RenderFont", font.to_ptr());
+        params.write_vector2d("ScreenPosition", x, y);
+        params.write_vector2d("Scale", scale, scale);
+        params.write("RenderColor", color);
+
+        let text = FString::from(text);
+        params.write("RenderText", text);
+
+        params.call();
+
+        // The engine only borrowed the string, so it's still ours to free
+        params.read::<FString>("RenderText");
+    }
+
+    pub fn draw_line(
+        &self,
+        x1: f32,
+        y1: f32,
+        x2: f32,
+        y2: f32,
+        color: FLinearColor,
+        thickness: f32,
+    ) {
+        let Some(mut params) = self.params("K2_DrawLine") else {
+            return;
+        };
+
+        params.write_vector2d("ScreenPositionA", x1, y1);
+        params.write_vector2d("ScreenPositionB", x2, y2);
+        params.write("Thickness", thickness);
+        params.write("RenderColor", color);
+
+        params.call();
+    }
+
+    /// Draws a filled rectangle
+    pub fn draw_rect(&self, x: f32, y: f32, w: f32, h: f32, color: FLinearColor) {
+        let Some(mut params) = self.params("K2_DrawTexture") else {
+            return;
+        };
+
+        // Without a texture the engine uses a white texture, which is tinted by the color
+        params.write("RenderTexture", std::ptr::null_mut::<c_void>());
+        params.write_vector2d("ScreenPosition", x, y);
+        params.write_vector2d("ScreenSize", w, h);
+        params.write_vector2d("CoordinateSize", 1.0, 1.0);
+        params.write("RenderColor", color);
+        params.write("BlendMode", BLEND_TRANSLUCENT);
+
+        params.call();
+    }
+
+    fn params(&self, function: &str) -> Option<Params> {
+        let object = self.find_object()?;
+        let function = object.get_class()?.find_function(function);
+
+        Params::new(object, UFunction::from_ptr_safe(function.to_ptr())?)
+    }
+
+    /// Finds the UCanvas wrapping this FCanvas, by looking for its pointer in the memory of every UCanvas
+    fn find_object(&self) -> Option<UObject> {
+        let key = self.0 as usize;
+        let mut objects = CANVAS_OBJECTS.lock().unwrap();
+
+        if let Some(object) = objects.get(&key) {
+            let object = UObject::from_ptr(*object as *mut c_void);
+
+            if self.is_wrapped_by(object) {
+                return Some(object);
+            }
+
+            objects.remove(&key);
+        }
+
+        let class = API::get().find_uobject::<UClass>("Class /Script/Engine.Canvas")?;
+        let object = class
+            .get_objects_matching_raw(false)
+            .into_iter()
+            .find(|object| self.is_wrapped_by(*object))?;
+
+        objects.insert(key, object.to_ptr() as usize);
+        Some(object)
+    }
+
+    fn is_wrapped_by(&self, object: UObject) -> bool {
+        let Some(class) = object.get_class() else {
+            return false;
+        };
+
+        let size = class.get_properties_size().max(0) as usize / size_of::<usize>();
+        let memory = unsafe { std::slice::from_raw_parts(object.to_ptr() as *const usize, size) };
+
+        memory.contains(&(self.0 as usize))
+    }
+}
+
+/// Parameters for a UFunction call, written by name so the layout of the engine version is used
+struct Params {
+    object: UObject,
+    function: UFunction,
+    data: Vec<u64>,
+}
+
+impl Params {
+    fn new(object: UObject, function: UFunction) -> Option<Self> {
+        let size = usize::try_from(function.get_properties_size()).ok()?;
+
+        Some(Self {
+            object,
+            function,
+            data: vec![0; size.div_ceil(size_of::<u64>())],
+        })
+    }
+
+    fn property(&self, name: &str) -> Option<FProperty> {
+        FProperty::from_ptr_safe(self.function.find_property(name).to_ptr())
+    }
+
+    fn write<T>(&mut self, name: &str, value: T) {
+        let Some(property) = self.property(name) else {
+            return;
+        };
+
+        unsafe {
+            let ptr = self.data.as_mut_ptr().byte_add(property.get_offset() as _) as *mut T;
+            ptr.write_unaligned(value);
+        }
+    }
+
+    fn read<T>(&self, name: &str) -> Option<T> {
+        let property = self.property(name)?;
+
+        unsafe {
+            let ptr = self.data.as_ptr().byte_add(property.get_offset() as _) as *const T;
+            Some(ptr.read_unaligned())
+        }
+    }
+
+    /// Writes an FVector2D, which uses doubles since UE 5.0
+    fn write_vector2d(&mut self, name: &str, x: f32, y: f32) {
+        let Some(property) = self.property(name) else {
+            return;
+        };
+
+        let size = FStructProperty::from_ptr(property.to_ptr())
+            .get_struct()
+            .get_properties_size();
+
+        if size == 2 * size_of::<f64>() as i32 {
+            self.write(name, [x as f64, y as f64]);
+        } else {
+            self.write(name, [x, y]);
+        }
+    }
+
+    fn call(&mut self) {
+        self.object
+            .process_event(self.function, self.data.as_mut_ptr() as _);
+    }
+}
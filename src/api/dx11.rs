@@ -0,0 +1,26 @@
+use crate::bindings::{UEVR_RendererData, UEVR_RENDERER_D3D11};
+
+use windows::{
+    core::Interface,
+    Win32::Graphics::{Direct3D11::ID3D11Device, Dxgi::IDXGISwapChain},
+};
+
+/// Returns the D3D11 device of the game, or `None` if the game renders using D3D12
+pub fn get_device() -> Option<ID3D11Device> {
+    let renderer = renderer()?;
+
+    unsafe { ID3D11Device::from_raw_borrowed(&renderer.device).cloned() }
+}
+
+/// Returns the swapchain the game presents to
+pub fn get_swapchain() -> Option<IDXGISwapChain> {
+    let renderer = renderer()?;
+
+    unsafe { IDXGISwapChain::from_raw_borrowed(&renderer.swapchain).cloned() }
+}
+
+fn renderer<'a>() -> Option<&'a UEVR_RendererData> {
+    let renderer = unsafe { &*super::API::get().param().renderer };
+
+    (renderer.renderer_type as u32 == UEVR_RENDERER_D3D11).then_some(renderer)
+}
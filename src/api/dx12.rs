@@ -0,0 +1,36 @@
+use crate::bindings::{UEVR_RendererData, UEVR_RENDERER_D3D12};
+
+use windows::{
+    core::Interface,
+    Win32::Graphics::{
+        Direct3D12::{ID3D12CommandQueue, ID3D12Device},
+        Dxgi::IDXGISwapChain3,
+    },
+};
+
+/// Returns the D3D12 device of the game, or `None` if the game renders using D3D11
+pub fn get_device() -> Option<ID3D12Device> {
+    let renderer = renderer()?;
+
+    unsafe { ID3D12Device::from_raw_borrowed(&renderer.device).cloned() }
+}
+
+/// Returns the queue the game submits its command lists to
+pub fn get_command_queue() -> Option<ID3D12CommandQueue> {
+    let renderer = renderer()?;
+
+    unsafe { ID3D12CommandQueue::from_raw_borrowed(&renderer.command_queue).cloned() }
+}
+
+/// Returns the swapchain the game presents to
+pub fn get_swapchain() -> Option<IDXGISwapChain3> {
+    let renderer = renderer()?;
+
+    unsafe { IDXGISwapChain3::from_raw_borrowed(&renderer.swapchain).cloned() }
+}
+
+fn renderer<'a>() -> Option<&'a UEVR_RendererData> {
+    let renderer = unsafe { &*super::API::get().param().renderer };
+
+    (renderer.renderer_type as u32 == UEVR_RENDERER_D3D12).then_some(renderer)
+}
@@ -0,0 +1,173 @@
+use crate::api::{FunctionHookGuard, Ptr, UFunction};
+
+use std::{
+    ffi::c_void,
+    sync::{Arc, Mutex},
+};
+
+/// Called with the object a function is called on and the stack frame of the call
+pub type FunctionHook = fn(obj: *mut c_void, stack: &FFrame);
+
+/// Start of an engine FFrame, the same in UE4 and UE5
+///
+/// When a function is called through `ProcessEvent`, [`FFrame::locals`] points to the parameters. Calls
+/// from Blueprint bytecode pass the parameters in the bytecode instead, so there the locals belong to the caller.
+#[repr(C)]
+pub struct FFrame {
+    vtable: *const c_void,
+    /// `bSuppressEventTag` and `bAutoEmitLineTerminator` of the FOutputDevice base, padded
+    output_device_flags: [u8; 8],
+    node: *mut c_void,
+    object: *mut c_void,
+    code: *mut u8,
+    locals: *mut u8,
+}
+
+const _: () = assert!(std::mem::offset_of!(FFrame, locals) == 0x28);
+
+impl FFrame {
+    /// The function being executed
+    pub fn node(&self) -> UFunction {
+        UFunction::from_ptr(self.node)
+    }
+
+    /// The object the function is executed on
+    pub fn object(&self) -> *mut c_void {
+        self.object
+    }
+
+    /// The parameters, if the function was called through `ProcessEvent`
+    pub fn locals(&self) -> *mut c_void {
+        self.locals as _
+    }
+}
+
+/// Signature of a native UFunction implementation, `(Context, Stack, Result)`
+type NativeFunction = unsafe extern "C" fn(*mut c_void, *mut c_void, *mut c_void);
+
+/// Amount of functions that can be hooked at the same time, every one needs its own trampoline
+const SLOT_COUNT: usize = 32;
+
+macro_rules! trampolines {
+    ($($slot:literal)*) => {
+        [$(trampoline::<$slot> as NativeFunction),*]
+    };
+}
+
+static TRAMPOLINES: [NativeFunction; SLOT_COUNT] = trampolines!(
+    0 1 2 3 4 5 6 7 8 9 10 11 12 13 14 15 16 17 18 19 20 21 22 23 24 25 26 27 28 29 30 31
+);
+
+/// Hooked functions, indexed by the trampoline that replaced their native function
+static HOOKED_FUNCTIONS: Mutex<[Option<HookedFunction>; SLOT_COUNT]> =
+    Mutex::new([const { None }; SLOT_COUNT]);
+
+/// Replaced as a whole when a hook is added, so calls only have to clone the Arc
+type Hooks = Arc<[FunctionHook]>;
+
+struct HookedFunction {
+    function: usize,
+    guard: FunctionHookGuard,
+    pre: Hooks,
+    post: Hooks,
+}
+
+/// Calls `hook` before the native implementation of `func` runs
+pub fn register_pre(func: UFunction, hook: FunctionHook) {
+    register(func, hook, true);
+}
+
+/// Calls `hook` after the native implementation of `func` has run
+pub fn register_post(func: UFunction, hook: FunctionHook) {
+    register(func, hook, false);
+}
+
+/// Removes every hook, putting the original native functions back
+pub fn unregister_all() {
+    // Dropping the guards restores the functions
+    let hooked = std::mem::replace(
+        &mut *HOOKED_FUNCTIONS.lock().unwrap(),
+        [const { None }; SLOT_COUNT],
+    );
+
+    drop(hooked);
+}
+
+fn register(func: UFunction, hook: FunctionHook, pre: bool) {
+    if func.is_invalid() {
+        return;
+    }
+
+    let mut hooked = HOOKED_FUNCTIONS.lock().unwrap();
+
+    let existing = hooked
+        .iter_mut()
+        .flatten()
+        .find(|function| function.function == func.to_ptr() as usize);
+
+    let function = match existing {
+        Some(function) => function,
+        None => {
+            let Some(slot) = hooked.iter().position(Option::is_none) else {
+                crate::error!("Can't hook more than {SLOT_COUNT} functions");
+                return;
+            };
+
+            let Some(guard) = func.set_hook(TRAMPOLINES[slot]) else {
+                crate::error!("Failed to find the native function pointer of a UFunction");
+                return;
+            };
+
+            hooked[slot].insert(HookedFunction {
+                function: func.to_ptr() as usize,
+                guard,
+                pre: Arc::new([]),
+                post: Arc::new([]),
+            })
+        }
+    };
+
+    let hooks = if pre {
+        &mut function.pre
+    } else {
+        &mut function.post
+    };
+
+    *hooks = hooks.iter().copied().chain([hook]).collect();
+}
+
+unsafe extern "C" fn trampoline<const SLOT: usize>(
+    context: *mut c_void,
+    stack: *mut c_void,
+    result: *mut c_void,
+) {
+    // The hooks are taken out of the lock so they can register more hooks without deadlocking
+    let Some((original, pre, post)) = copy_hooks(SLOT) else {
+        return;
+    };
+
+    if let Some(frame) = (stack as *const FFrame).as_ref() {
+        for hook in pre.iter() {
+            hook(context, frame);
+        }
+    }
+
+    original(context, stack, result);
+
+    if let Some(frame) = (stack as *const FFrame).as_ref() {
+        for hook in post.iter() {
+            hook(context, frame);
+        }
+    }
+}
+
+fn copy_hooks(slot: usize) -> Option<(NativeFunction, Hooks, Hooks)> {
+    let hooked = HOOKED_FUNCTIONS.lock().unwrap();
+    let function = hooked[slot].as_ref()?;
+
+    Some((
+        function.guard.original(),
+        Arc::clone(&function.pre),
+        Arc::clone(&function.post),
+    ))
+}
@@ -0,0 +1,86 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use windows::Win32::UI::WindowsAndMessaging::{WM_KEYDOWN, WM_KEYUP, WM_SYSKEYDOWN, WM_SYSKEYUP};
+
+static KEYBOARD_STATE: KeyboardState = KeyboardState::new();
+
+/// State of every virtual key, updated from the window messages of the game
+///
+/// Presses and releases are collected between ticks and taken over at the start of every engine tick, so
+/// the `just_*` flags stay the same for the whole tick and an event that arrives mid-tick is reported in
+/// the next one.
+pub struct KeyboardState {
+    down: [AtomicBool; 256],
+    pending_pressed: [AtomicBool; 256],
+    pending_released: [AtomicBool; 256],
+    just_pressed: [AtomicBool; 256],
+    just_released: [AtomicBool; 256],
+}
+
+impl KeyboardState {
+    const fn new() -> Self {
+        #[allow(clippy::declare_interior_mutable_const)]
+        const UP: AtomicBool = AtomicBool::new(false);
+
+        Self {
+            down: [UP; 256],
+            pending_pressed: [UP; 256],
+            pending_released: [UP; 256],
+            just_pressed: [UP; 256],
+            just_released: [UP; 256],
+        }
+    }
+
+    /// Whether the key with virtual key code `key` is held down
+    pub fn is_down(&self, key: u8) -> bool {
+        self.down[key as usize].load(Ordering::Relaxed)
+    }
+
+    /// Whether the key was pressed before the current engine tick started, since the previous one
+    pub fn was_just_pressed(&self, key: u8) -> bool {
+        self.just_pressed[key as usize].load(Ordering::Relaxed)
+    }
+
+    /// Whether the key was released before the current engine tick started, since the previous one
+    pub fn was_just_released(&self, key: u8) -> bool {
+        self.just_released[key as usize].load(Ordering::Relaxed)
+    }
+}
+
+pub fn keyboard_state() -> &'static KeyboardState {
+    &KEYBOARD_STATE
+}
+
+pub(crate) fn handle_message(msg: u32, wparam: u64) {
+    let Ok(key) = u8::try_from(wparam) else {
+        return;
+    };
+
+    let state = &KEYBOARD_STATE;
+    let key = key as usize;
+
+    let (down, transitions) = match msg {
+        WM_KEYDOWN | WM_SYSKEYDOWN => (true, &state.pending_pressed),
+        WM_KEYUP | WM_SYSKEYUP => (false, &state.pending_released),
+        _ => return,
+    };
+
+    // Held keys repeat the down message, which shouldn't count as a new press
+    if state.down[key].swap(down, Ordering::Relaxed) != down {
+        transitions[key].store(true, Ordering::Relaxed);
+    }
+}
+
+/// Takes over the transitions collected since the previous tick, resetting them in the same swap
+pub(crate) fn snapshot_transitions() {
+    let state = &KEYBOARD_STATE;
+
+    for (pending, just) in state
+        .pending_pressed
+        .iter()
+        .zip(&state.just_pressed)
+        .chain(state.pending_released.iter().zip(&state.just_released))
+    {
+        just.store(pending.swap(false, Ordering::Relaxed), Ordering::Relaxed);
+    }
+}
@@ -0,0 +1,251 @@
+//! Fake UEVR SDK for unit tests
+//!
+//! [`install`] initializes [`API`] with function tables that are zeroed except for the functions mocked
+//! here. Their state is thread local, so tests running in parallel don't see each other's calls.
+
+use crate::{
+    api::{FUObjectItem, API},
+    bindings::{
+        UEVR_ConsoleFunctions, UEVR_FConsoleManagerHandle, UEVR_FMallocFunctions,
+        UEVR_FMallocHandle, UEVR_PluginFunctions, UEVR_PluginInitializeParam, UEVR_Quaternionf,
+        UEVR_SDKData, UEVR_TArrayHandle, UEVR_UClassHandle, UEVR_UObjectArrayFunctions,
+        UEVR_UObjectArrayHandle, UEVR_UObjectHandle, UEVR_UObjectHookFunctions,
+        UEVR_UObjectHookMotionControllerStateFunctions,
+        UEVR_UObjectHookMotionControllerStateHandle, UEVR_Vector3f,
+    },
+};
+
+use std::{
+    alloc::{alloc, dealloc, Layout},
+    cell::{Cell, RefCell},
+    collections::HashMap,
+    ffi::{c_char, c_void},
+    mem::zeroed,
+    sync::Once,
+};
+
+/// Size of the header in front of every mocked allocation, which stores the requested size
+const HEADER: usize = 16;
+
+thread_local! {
+    static LIVE_ALLOCATIONS: Cell<isize> = const { Cell::new(0) };
+    static OBJECTS: RefCell<HashMap<String, usize>> = RefCell::new(HashMap::new());
+    static FIND_UOBJECT_CALLS: RefCell<HashMap<String, usize>> = RefCell::new(HashMap::new());
+    static OBJECT_ARRAY: Cell<(usize, i32, bool, bool)> = const { Cell::new((0, 0, false, false)) };
+    static CLASS_OBJECTS: RefCell<(usize, Vec<usize>)> = const { RefCell::new((0, vec![])) };
+}
+
+/// Initializes the API with the fake SDK, does nothing if that already happened
+pub(crate) fn install() {
+    static INSTALL: Once = Once::new();
+
+    INSTALL.call_once(|| unsafe {
+        // The log functions are variadic, which can't be defined on stable. The extra arguments are never
+        // passed by this crate, so calling a non-variadic function instead is fine.
+        let log = Some(std::mem::transmute::<
+            unsafe extern "C" fn(*const c_char),
+            unsafe extern "C" fn(*const c_char, ...),
+        >(log));
+
+        let functions = leak(UEVR_PluginFunctions {
+            log_error: log,
+            log_warn: log,
+            log_info: log,
+            ..zeroed()
+        });
+
+        let sdk = leak(UEVR_SDKData {
+            uobject_array: leak(UEVR_UObjectArrayFunctions {
+                find_uobject: Some(find_uobject),
+                get_objects_ptr: Some(get_objects_ptr),
+                get_object_count: Some(get_object_count),
+                is_chunked: Some(is_chunked),
+                is_inlined: Some(is_inlined),
+                get_item_distance: Some(get_item_distance),
+                ..zeroed()
+            }),
+            malloc: leak(UEVR_FMallocFunctions {
+                get: Some(get_malloc),
+                malloc: Some(malloc),
+                realloc: Some(realloc),
+                free: Some(free),
+            }),
+            console: leak(UEVR_ConsoleFunctions {
+                get_console_objects: Some(get_console_objects),
+                ..zeroed()
+            }),
+            uobject_hook: leak(UEVR_UObjectHookFunctions {
+                activate: Some(activate),
+                get_objects_by_class: Some(get_objects_by_class),
+                // UEVR keeps no state that can be read back, so the setters don't have to do anything
+                mc_state: leak(UEVR_UObjectHookMotionControllerStateFunctions {
+                    set_rotation_offset: Some(set_rotation_offset),
+                    set_location_offset: Some(set_location_offset),
+                    set_hand: Some(set_hand),
+                    set_permanent: Some(set_permanent),
+                }) as _,
+                ..zeroed()
+            }),
+            ..zeroed()
+        });
+
+        API::initialize(leak(UEVR_PluginInitializeParam {
+            functions,
+            sdk,
+            ..zeroed()
+        }));
+    });
+}
+
+/// Amount of mocked FMalloc allocations made by this thread that haven't been freed
+pub(crate) fn live_allocations() -> isize {
+    LIVE_ALLOCATIONS.with(Cell::get)
+}
+
+/// Makes the mocked `find_uobject` return `object` for the full name `name` on this thread
+pub(crate) fn add_object(name: &str, object: *mut c_void) {
+    OBJECTS.with_borrow_mut(|objects| objects.insert(name.to_string(), object as usize));
+}
+
+/// How often `find_uobject` was called with the full name `name` on this thread
+pub(crate) fn find_uobject_calls(name: &str) -> usize {
+    FIND_UOBJECT_CALLS.with_borrow(|calls| calls.get(name).copied().unwrap_or(0))
+}
+
+/// Makes the mocked `get_objects_by_class` report `counted` objects when asked for the amount, and list
+/// `objects` when asked for the objects themselves, like objects being destroyed in between
+pub(crate) fn set_class_objects(counted: usize, objects: &[*mut c_void]) {
+    CLASS_OBJECTS.set((
+        counted,
+        objects.iter().map(|&object| object as usize).collect(),
+    ));
+}
+
+/// Describes the object array, `objects` is what `get_objects_ptr` returns: the address of the items when
+/// they're inlined, or of a pointer to the (chunk table of the) items otherwise
+pub(crate) fn set_object_array(objects: *mut c_void, count: i32, chunked: bool, inlined: bool) {
+    OBJECT_ARRAY.set((objects as usize, count, chunked, inlined));
+}
+
+fn leak<T>(value: T) -> *const T {
+    Box::into_raw(Box::new(value))
+}
+
+unsafe extern "C" fn log(_: *const c_char) {}
+
+unsafe extern "C" fn find_uobject(name: *const u16) -> UEVR_UObjectHandle {
+    let len = (0..).take_while(|&i| *name.add(i) != 0).count();
+    let name = String::from_utf16_lossy(std::slice::from_raw_parts(name, len));
+
+    FIND_UOBJECT_CALLS.with_borrow_mut(|calls| *calls.entry(name.clone()).or_default() += 1);
+    OBJECTS.with_borrow(|objects| objects.get(&name).copied().unwrap_or(0)) as _
+}
+
+unsafe extern "C" fn activate() {}
+
+unsafe extern "C" fn get_objects_by_class(
+    _: UEVR_UClassHandle,
+    out_objects: *mut UEVR_UObjectHandle,
+    max_objects: u32,
+    _allow_default: bool,
+) -> i32 {
+    CLASS_OBJECTS.with_borrow(|(counted, objects)| {
+        if out_objects.is_null() {
+            return *counted as _;
+        }
+
+        let written = objects.len().min(max_objects as _);
+        for (i, &object) in objects[..written].iter().enumerate() {
+            out_objects.add(i).write(object as _);
+        }
+
+        written as _
+    })
+}
+
+/// Tests pass the address of the console object array as the console manager
+unsafe extern "C" fn get_console_objects(manager: UEVR_FConsoleManagerHandle) -> UEVR_TArrayHandle {
+    manager as _
+}
+
+unsafe extern "C" fn get_objects_ptr(_: UEVR_UObjectArrayHandle) -> *mut c_void {
+    OBJECT_ARRAY.get().0 as _
+}
+
+unsafe extern "C" fn get_object_count(_: UEVR_UObjectArrayHandle) -> i32 {
+    OBJECT_ARRAY.get().1
+}
+
+unsafe extern "C" fn is_chunked() -> bool {
+    OBJECT_ARRAY.get().2
+}
+
+unsafe extern "C" fn is_inlined() -> bool {
+    OBJECT_ARRAY.get().3
+}
+
+unsafe extern "C" fn get_item_distance() -> u32 {
+    std::mem::size_of::<FUObjectItem>() as _
+}
+
+unsafe extern "C" fn get_malloc() -> UEVR_FMallocHandle {
+    // Never dereferenced, the mocked functions ignore the instance
+    std::ptr::dangling_mut::<c_void>() as _
+}
+
+fn layout(size: usize) -> Layout {
+    Layout::from_size_align(size + HEADER, HEADER).unwrap()
+}
+
+unsafe extern "C" fn malloc(_: UEVR_FMallocHandle, size: u32, _alignment: u32) -> *mut c_void {
+    let base = alloc(layout(size as usize));
+    (base as *mut usize).write(size as usize);
+
+    LIVE_ALLOCATIONS.with(|live| live.set(live.get() + 1));
+    base.add(HEADER) as _
+}
+
+unsafe extern "C" fn realloc(
+    instance: UEVR_FMallocHandle,
+    ptr: *mut c_void,
+    size: u32,
+    alignment: u32,
+) -> *mut c_void {
+    let result = malloc(instance, size, alignment);
+
+    if !ptr.is_null() {
+        let old_size = *((ptr as *mut u8).sub(HEADER) as *const usize);
+
+        std::ptr::copy_nonoverlapping(ptr as *const u8, result as *mut u8, old_size.min(size as _));
+        free(instance, ptr);
+    }
+
+    result
+}
+
+unsafe extern "C" fn free(_: UEVR_FMallocHandle, ptr: *mut c_void) {
+    if ptr.is_null() {
+        return;
+    }
+
+    let base = (ptr as *mut u8).sub(HEADER);
+    dealloc(base, layout(*(base as *const usize)));
+
+    LIVE_ALLOCATIONS.with(|live| live.set(live.get() - 1));
+}
+
+unsafe extern "C" fn set_rotation_offset(
+    _: UEVR_UObjectHookMotionControllerStateHandle,
+    _: *const UEVR_Quaternionf,
+) {
+}
+
+unsafe extern "C" fn set_location_offset(
+    _: UEVR_UObjectHookMotionControllerStateHandle,
+    _: *const UEVR_Vector3f,
+) {
+}
+
+unsafe extern "C" fn set_hand(_: UEVR_UObjectHookMotionControllerStateHandle, _: u32) {}
+
+unsafe extern "C" fn set_permanent(_: UEVR_UObjectHookMotionControllerStateHandle, _: bool) {}
@@ -1,7 +1,18 @@
+pub mod canvas;
+pub mod dx11;
+pub mod dx12;
+pub mod fn_hooks;
+pub mod keyboard;
+#[cfg(test)]
+mod mock;
 pub mod object_hook;
+#[cfg(feature = "registry")]
+pub mod registry;
 pub mod render_hook;
 pub mod stereo_hook;
+pub mod storage;
 pub mod vr;
+pub mod xinput;
 
 use crate::{
     self as rusty_uevr,
@@ -9,23 +20,68 @@ use crate::{
         wchar_t, UEVR_FFieldHandle, UEVR_FPropertyHandle, UEVR_IConsoleObjectHandle,
         UEVR_PluginInitializeParam, UEVR_Quaternionf, UEVR_SDKData, UEVR_SDKFunctions,
         UEVR_UFieldHandle, UEVR_UObjectHandle, UEVR_UStructHandle, UEVR_Vector3f,
+        UEVR_RENDERER_D3D11, UEVR_RENDERER_D3D12,
     },
     define_object,
     util::encode_wstr,
 };
 
+use windows::{
+    core::Interface,
+    Win32::Graphics::{Direct3D11::ID3D11Texture2D, Direct3D12::ID3D12Resource},
+};
+
 use std::{
+    alloc::{GlobalAlloc, Layout},
+    any::type_name,
+    cell::Cell,
+    collections::{HashMap, HashSet},
     ffi::{c_void, CString},
-    iter,
-    mem::ManuallyDrop,
+    fmt, iter,
+    marker::PhantomData,
+    mem::{offset_of, size_of, zeroed, ManuallyDrop},
+    ops::{Deref, DerefMut},
     path::PathBuf,
-    ptr::{null, null_mut},
-    sync::{Arc, LazyLock, Mutex},
+    ptr::{null, null_mut, NonNull},
+    sync::{
+        atomic::{AtomicPtr, AtomicU64, Ordering},
+        Arc, LazyLock, Mutex,
+    },
 };
 
 // TODO: Does this Arc actually achieve anything? Is it needed in a multithreading context?
 static INSTANCE: LazyLock<Arc<Mutex<Option<API>>>> = LazyLock::new(|| Arc::new(Mutex::new(None)));
 
+/// Cache used by [`FName::to_string_cached`], keyed on the raw 8-byte FName value (index + number)
+static NAME_CACHE: LazyLock<Mutex<HashMap<u64, String>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Cast errors that were already logged by [`Ptr::cast_or_log`]
+static LOGGED_CAST_ERRORS: LazyLock<Mutex<HashSet<String>>> =
+    LazyLock::new(|| Mutex::new(HashSet::new()));
+
+/// Names of console variables that were already reported missing by [`CVar::find_or_log`]
+static LOGGED_MISSING_CVARS: LazyLock<Mutex<HashSet<String>>> =
+    LazyLock::new(|| Mutex::new(HashSet::new()));
+
+/// Watchers registered using [`CVarWatcher::register`], polled every engine tick
+static CVAR_WATCHERS: Mutex<Vec<CVarWatcher>> = Mutex::new(Vec::new());
+
+/// Watchers registered using [`PropertyWatcher::register`], polled every engine tick
+static PROPERTY_WATCHERS: Mutex<Vec<PropertyWatcher>> = Mutex::new(Vec::new());
+
+/// Closures queued by [`run_on_game_thread`], executed before the next engine tick
+static GAME_THREAD_QUEUE: Mutex<Vec<Box<dyn FnOnce() + Send>>> = Mutex::new(Vec::new());
+
+/// Amount of engine ticks so far, see [`get_frame_number`]
+static FRAME_NUMBER: AtomicU64 = AtomicU64::new(0);
+
+/// Sum of all engine tick deltas as the bits of an f64, see [`get_elapsed_game_time`]
+static ELAPSED_GAME_TIME: AtomicU64 = AtomicU64::new(0);
+
+/// Every per-type class cache that currently holds a value, used by [`invalidate_static_class_cache`]
+static STATIC_CLASS_CACHES: Mutex<Vec<&'static AtomicPtr<c_void>>> = Mutex::new(Vec::new());
+
 #[derive(Clone)]
 pub struct API {
     param: *const UEVR_PluginInitializeParam,
@@ -172,6 +228,41 @@ impl API {
         unsafe { UObject::from_handle(fun(index)) }
     }
 
+    /// Returns the width and height of the game viewport in pixels, or `(0, 0)` if it can't be determined
+    ///
+    /// Asks the first player controller for its viewport size, falling back to the size of UEVR's UI
+    /// render target, which the game viewport is rendered into while in VR.
+    pub fn get_viewport_size(&self) -> (u32, u32) {
+        #[repr(C)]
+        struct Params {
+            size_x: i32,
+            size_y: i32,
+        }
+
+        let fun = self.functions().get_player_controller.unwrap();
+
+        if let Some(controller) = unsafe { UObject::from_handle_safe(fun(0)) } {
+            let mut params = Params {
+                size_x: 0,
+                size_y: 0,
+            };
+
+            controller.call_function("GetViewportSize", &mut params as *mut Params as _);
+
+            if params.size_x > 0 && params.size_y > 0 {
+                return (params.size_x as u32, params.size_y as u32);
+            }
+        }
+
+        if stereo_hook::is_active() {
+            if let Some(size) = stereo_hook::get_ui_render_target_size() {
+                return size;
+            }
+        }
+
+        (0, 0)
+    }
+
     pub fn get_local_pawn(&self, index: i32) -> UObject {
         let fun = self.functions().get_local_pawn.unwrap();
 
@@ -246,12 +337,93 @@ pub trait Ptr {
         }
     }
 
+    /// Same as [`Ptr::cast`], but returns the reason why the cast failed
+    fn try_cast<T: StaticClass>(&self) -> Result<T, CastError>
+    where
+        Self: StaticClass,
+    {
+        let Some(class) = T::static_class_safe() else {
+            return Err(CastError::ClassNotFound {
+                target: type_name::<T>(),
+            });
+        };
+
+        if self.is_a(class) {
+            return Ok(T::from_ptr(self.to_ptr()));
+        }
+
+        let source_class = UObject::from_ptr(self.to_ptr())
+            .get_class()
+            .map(|class| class.get_fname().to_string())
+            .unwrap_or_default();
+
+        Err(CastError::Mismatch {
+            source_class,
+            expected_class: class.get_full_name(),
+        })
+    }
+
+    /// Same as [`Ptr::cast`], but logs the reason why the cast failed
+    ///
+    /// Every distinct failure is only logged once.
+    fn cast_or_log<T: StaticClass>(&self) -> Option<T>
+    where
+        Self: StaticClass,
+    {
+        match self.try_cast() {
+            Ok(result) => Some(result),
+            Err(error) => {
+                let message = error.to_string();
+
+                if LOGGED_CAST_ERRORS.lock().unwrap().insert(message.clone()) {
+                    warn!("{message}");
+                }
+
+                None
+            }
+        }
+    }
+
     unsafe fn unsafe_cast<T: Ptr>(&self) -> T {
         T::from_ptr(self.to_ptr())
     }
 }
 
+#[derive(Debug, Clone)]
+pub enum CastError {
+    /// The class of the target type could not be found
+    ClassNotFound { target: &'static str },
+    /// The object is not an instance of the target class
+    Mismatch {
+        source_class: String,
+        expected_class: String,
+    },
+}
+
+impl fmt::Display for CastError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ClassNotFound { target } => {
+                write!(f, "cannot cast to {target}: its class could not be found")
+            }
+            Self::Mismatch {
+                source_class,
+                expected_class,
+            } => write!(
+                f,
+                "cannot cast an object of class {source_class} to {expected_class}"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for CastError {}
+
 pub trait StaticClass: Ptr {
+    /// Returns the class of the type, or `None` if it isn't loaded
+    ///
+    /// The implementations generated by `define_object!` only search the object array until the class
+    /// is found, after that this is a single atomic load until [`invalidate_static_class_cache`] is called.
     fn static_class_safe() -> Option<UClass>;
 
     fn static_class() -> UClass {
@@ -267,17 +439,80 @@ pub trait StaticClass: Ptr {
     }
 }
 
+/// Returns the function table stored in `cache`, storing the one returned by `init` on first use
+///
+/// Used by the function tables of the hook modules and the types generated by `define_object!`.
+/// Racing threads may both call `init`, which is fine as UEVR always returns the same table.
+#[doc(hidden)]
+pub fn load_function_table<T>(cache: &AtomicPtr<T>, init: impl FnOnce() -> *const T) -> *const T {
+    let ptr = cache.load(Ordering::Acquire);
+    if !ptr.is_null() {
+        return ptr;
+    }
+
+    let ptr = init() as *mut T;
+    cache.store(ptr, Ordering::Release);
+
+    ptr
+}
+
+/// Looks up a class by its path, reusing the result stored in `cache` on subsequent calls
+///
+/// Used by the [`StaticClass`] implementations generated by `define_object!`.
+#[doc(hidden)]
+pub fn find_static_class_cached(
+    cache: &'static AtomicPtr<c_void>,
+    name: impl AsRef<str>,
+) -> Option<UClass> {
+    find_object_cached(cache, || API::get().find_uobject::<UClass>(name))
+}
+
+/// Returns the object stored in `cache`, storing the one returned by `find` on first use
+///
+/// The cache is cleared together with the [`StaticClass`] caches by [`invalidate_static_class_cache`].
+pub(crate) fn find_object_cached<T: Ptr>(
+    cache: &'static AtomicPtr<c_void>,
+    find: impl FnOnce() -> Option<T>,
+) -> Option<T> {
+    let ptr = cache.load(Ordering::Acquire);
+    if !ptr.is_null() {
+        return Some(T::from_ptr(ptr));
+    }
+
+    let object = find()?;
+
+    let mut caches = STATIC_CLASS_CACHES.lock().unwrap();
+    cache.store(object.to_ptr(), Ordering::Release);
+    caches.push(cache);
+
+    Some(object)
+}
+
+/// Clears all cached [`StaticClass`] lookups, and the functions and default objects cached by this crate
+///
+/// Class objects can be recreated during level transitions, so call this when a new level is loaded.
+pub fn invalidate_static_class_cache() {
+    let mut caches = STATIC_CLASS_CACHES.lock().unwrap();
+
+    for cache in caches.drain(..) {
+        cache.store(null_mut(), Ordering::Release);
+    }
+}
+
 define_object!(
     FMalloc,
     @functions(UEVR_FMallocHandle, UEVR_FMallocFunctions, malloc)
 );
 
 define_object!(
+    /// Engine-wide interned name
     FName,
     @functions(UEVR_FNameHandle, UEVR_FNameFunctions, fname)
 );
 
+#[cfg_attr(feature = "registry", crate::static_class)]
 define_object!(
+    /// Base type of every object managed by the engine
     UObject,
     "Object",
     @functions(UEVR_UObjectHandle, UEVR_UObjectFunctions, uobject),
@@ -285,6 +520,7 @@ define_object!(
     @impls(RUObject)
 );
 
+#[cfg_attr(feature = "registry", crate::static_class)]
 define_object!(
     UField,
     "Field",
@@ -293,6 +529,7 @@ define_object!(
     @impls(RUObject, RUField)
 );
 
+#[cfg_attr(feature = "registry", crate::static_class)]
 define_object!(
     UStruct,
     "Struct",
@@ -301,7 +538,9 @@ define_object!(
     @impls(RUObject, RUField, RUStruct)
 );
 
+#[cfg_attr(feature = "registry", crate::static_class)]
 define_object!(
+    /// Reflection data of a class, used to find and create objects of that class
     UClass,
     "Class",
     @functions(UEVR_UClassHandle, UEVR_UClassFunctions, uclass),
@@ -309,7 +548,9 @@ define_object!(
     @impls(RUObject, RUField, RUStruct)
 );
 
+#[cfg_attr(feature = "registry", crate::static_class)]
 define_object!(
+    /// Reflected function, can be called on objects using `RUObject::process_event`
     UFunction,
     "Function",
     @functions(UEVR_UFunctionHandle, UEVR_UFunctionFunctions, ufunction),
@@ -317,6 +558,7 @@ define_object!(
     @impls(RUObject, RUField, RUStruct)
 );
 
+#[cfg_attr(feature = "registry", crate::static_class)]
 define_object!(
     UScriptStruct,
     "ScriptStruct",
@@ -330,6 +572,7 @@ define_object!(
     @functions(UEVR_UObjectArrayHandle, UEVR_UObjectArrayFunctions, uobject_array)
 );
 
+#[cfg_attr(feature = "registry", crate::static_class)]
 define_object!(
     UEnum,
     "Enum",
@@ -338,13 +581,15 @@ define_object!(
 
 define_object!(
     FField,
-    @functions(UEVR_FFieldHandle, UEVR_FFieldFunctions, ffield)
+    @functions(UEVR_FFieldHandle, UEVR_FFieldFunctions, ffield),
+    @impls(RFField)
 );
 
 define_object!(
+    /// Reflected property of a struct or class
     FProperty,
     @functions(UEVR_FPropertyHandle, UEVR_FPropertyFunctions, fproperty),
-    @impls(RFField)
+    @impls(RFField, RFProperty)
 );
 
 define_object!(
@@ -370,6 +615,11 @@ define_object!(
     @impls(RFField, RFProperty)
 );
 
+define_object!(
+    FSoftObjectProperty,
+    @impls(RFField, RFProperty)
+);
+
 define_object!(
     FEnumProperty,
     @functions(UEVR_FEnumPropertyHandle, UEVR_FEnumPropertyFunctions, fenumproperty),
@@ -393,10 +643,15 @@ define_object!(
 
 define_object!(
     IConsoleObject,
-    @functions(UEVR_IConsoleObjectHandle, UEVR_ConsoleFunctions, console)
+    @functions(UEVR_IConsoleObjectHandle, UEVR_ConsoleFunctions, console),
+    @impls(RIConsoleObject)
 );
 
 define_object!(
+    /// Console variable, read as an int or float and set from a string
+    ///
+    /// String values can't be read: UEVR only exposes `GetInt` and `GetFloat`, and calling `GetString`
+    /// through the vtable depends on the engine version. String variables read as 0.
     IConsoleVariable,
     @functions(UEVR_IConsoleVariableHandle, UEVR_ConsoleFunctions, console),
     @impls(RIConsoleObject)
@@ -409,6 +664,7 @@ define_object!(
 );
 
 // TODO
+#[cfg_attr(feature = "registry", crate::static_class)]
 define_object!(
     UEngine,
     "Engine",
@@ -416,6 +672,7 @@ define_object!(
 );
 
 // TODO
+#[cfg_attr(feature = "registry", crate::static_class)]
 define_object!(
     UGameEngine,
     "GameEngine",
@@ -423,7 +680,9 @@ define_object!(
 );
 
 // TODO
+#[cfg_attr(feature = "registry", crate::static_class)]
 define_object!(
+    /// World containing the current level and its actors
     UWorld,
     "World",
     @class("Class /Script/Engine.World"),
@@ -476,6 +735,34 @@ impl FName {
 
         String::from_utf16(&ptr[0..ptr.len() - 1]).unwrap()
     }
+
+    /// Same as [`FName::to_string`], but looks the name up in a global cache first
+    ///
+    /// The cache is keyed on the raw FName value, which includes the number suffix,
+    /// so names like `Mesh_2` and `Mesh_3` are cached separately.
+    pub fn to_string_cached(&self) -> String {
+        if self.is_invalid() {
+            return "".to_string();
+        }
+
+        let key = unsafe { *(self.to_ptr() as *const u64) };
+
+        if let Some(name) = NAME_CACHE.lock().unwrap().get(&key) {
+            return name.clone();
+        }
+
+        let name = self.to_string();
+        NAME_CACHE.lock().unwrap().insert(key, name.clone());
+
+        name
+    }
+
+    /// Clears the cache used by [`FName::to_string_cached`]
+    ///
+    /// Only needed if the game modifies the name table at runtime.
+    pub fn clear_name_cache() {
+        NAME_CACHE.lock().unwrap().clear();
+    }
 }
 
 impl FMalloc {
@@ -544,6 +831,37 @@ pub trait RUObject: Ptr {
         unsafe { &mut *self.get_property_data(name) }
     }
 
+    /// Reads a weak object pointer property, use [`FWeakObjectPtr::resolve`] to get the object
+    fn read_weak_object_property(&self, name: &str) -> Option<FWeakObjectPtr> {
+        let data = self.get_property_data::<FWeakObjectPtr>(name);
+
+        unsafe { data.as_ref().copied() }
+    }
+
+    /// Reads the path of a soft object pointer property, use [`FSoftObjectPath::try_resolve`] to get the object
+    fn read_soft_object_property(&self, name: &str) -> Option<&FSoftObjectPath> {
+        let data = self.get_property_data::<u8>(name);
+        if data.is_null() {
+            return None;
+        }
+
+        unsafe { Some(FSoftObjectPath::from_soft_object_ptr(data as _)) }
+    }
+
+    /// Reads a text property as a string, see [`FText::try_to_string`] for the supported engine versions
+    fn get_text_property(&self, name: &str) -> Option<String> {
+        let data = self.get_property_data::<FText>(name);
+
+        unsafe { data.as_ref()?.try_to_string() }
+    }
+
+    /// Reads an object property, returning `None` if the property doesn't exist or is null
+    fn read_object_property(&self, name: &str) -> Option<UObject> {
+        let data = self.get_property_data::<UEVR_UObjectHandle>(name);
+
+        unsafe { UObject::from_handle_safe(*data.as_ref()?) }
+    }
+
     fn get_bool_property(&self, name: &str) -> bool {
         let name = name.encode_utf16().chain(iter::once(0)).collect::<Vec<_>>();
         let fun = UObject::initialize().get_bool_property.unwrap();
@@ -565,8 +883,18 @@ pub trait RUObject: Ptr {
     }
 
     fn get_full_name(&self) -> String {
-        let Some(class) = self.get_class().and_then(|class| class.cast::<UObject>()) else {
+        let (class, path) = self.get_full_name_components();
+        if class.is_empty() {
             return "".to_string();
+        }
+
+        format!("{class} {path}")
+    }
+
+    /// Returns the class name and the object path (e.g. `Outer.Name`) separately
+    fn get_full_name_components(&self) -> (String, String) {
+        let Some(class) = self.get_class().and_then(|class| class.cast::<UObject>()) else {
+            return ("".to_string(), "".to_string());
         };
 
         let mut name = self.get_fname().to_string();
@@ -581,8 +909,124 @@ pub trait RUObject: Ptr {
             current = outer.get_outer();
         }
 
-        format!("{} {name}", class.get_fname().to_string())
+        (class.get_fname().to_string(), name)
+    }
+
+    /// Collects the names of all properties on this object's class and its parent classes
+    ///
+    /// Names are de-duplicated, keeping the first occurrence (closest to the object's class).
+    fn get_all_property_names(&self) -> Vec<String> {
+        let Some(class) = self.get_class() else {
+            return vec![];
+        };
+
+        let mut names = vec![];
+
+        for parent in class.parents_iter() {
+            for property in parent.properties_iter() {
+                let name = property.get_fname().to_string();
+
+                if !names.contains(&name) {
+                    names.push(name);
+                }
+            }
+        }
+
+        names
+    }
+}
+
+/// Resolves a `.`-separated property path like `RootComponent.RelativeLocation` on `obj`
+///
+/// Every segment but the last has to be an object or struct property, object properties are followed
+/// to the object they point to. Returns the address of the object or struct containing the last
+/// property together with the property, so its value is at `base + property.get_offset()`.
+pub fn resolve_property_path(obj: &impl RUObject, path: &str) -> Option<(*mut c_void, FProperty)> {
+    resolve_property_hops(obj, path).map(|(base, property, _)| (base, property))
+}
+
+/// Step from one segment of a property path to the next, see [`resolve_property_hops`]
+#[derive(Clone, Copy)]
+struct PropertyHop {
+    offset: usize,
+    /// Class of the object the pointer at `offset` pointed to, `None` for an embedded struct
+    object_class: Option<*mut c_void>,
+}
+
+/// Same as [`resolve_property_path`], also returning the steps taken so they can be repeated using
+/// [`follow_property_hops`] without looking up the properties again
+fn resolve_property_hops(
+    obj: &impl RUObject,
+    path: &str,
+) -> Option<(*mut c_void, FProperty, Vec<PropertyHop>)> {
+    let mut segments = path.split('.');
+    let last = segments.next_back()?;
+
+    let mut base = obj.to_ptr();
+    let mut current = UStruct::from_ptr_safe(obj.get_class()?.to_ptr())?;
+    let mut hops = vec![];
+
+    for segment in segments {
+        let property = FProperty::from_ptr_safe(current.find_property(segment).to_ptr())?;
+        let offset = property.get_offset() as usize;
+        let data = unsafe { (base as *mut u8).add(offset) };
+
+        match property.get_class().get_name().as_str() {
+            "ObjectProperty" | "ObjectPtrProperty" | "ClassProperty" => {
+                let object =
+                    unsafe { UObject::from_handle_safe(*(data as *const UEVR_UObjectHandle))? };
+                let class = object.get_class()?;
+
+                base = object.to_ptr();
+                current = UStruct::from_ptr_safe(class.to_ptr())?;
+                hops.push(PropertyHop {
+                    offset,
+                    object_class: Some(class.to_ptr()),
+                });
+            }
+            "StructProperty" => {
+                let inner = FStructProperty::from_ptr(property.to_ptr()).get_struct();
+
+                base = data as _;
+                current = UStruct::from_ptr_safe(inner.to_ptr())?;
+                hops.push(PropertyHop {
+                    offset,
+                    object_class: None,
+                });
+            }
+            _ => return None,
+        }
+    }
+
+    let property = FProperty::from_ptr_safe(current.find_property(last).to_ptr())?;
+
+    Some((base, property, hops))
+}
+
+/// Repeats the steps of a resolved property path starting at `base`
+///
+/// Returns `None` if an object along the way is gone or has a different class than when the path was
+/// resolved, in which case the path has to be resolved again.
+fn follow_property_hops(mut base: *mut c_void, hops: &[PropertyHop]) -> Option<*mut c_void> {
+    for hop in hops {
+        let data = unsafe { (base as *mut u8).add(hop.offset) };
+
+        base = match hop.object_class {
+            Some(class) => {
+                let object =
+                    unsafe { UObject::from_handle_safe(*(data as *const UEVR_UObjectHandle))? };
+
+                if !std::ptr::eq(object.get_class()?.to_ptr(), class) {
+                    return None;
+                }
+
+                object.to_ptr()
+            }
+            None => data as _,
+        };
     }
+
+    Some(base)
 }
 
 pub trait RUField: RUObject {
@@ -649,6 +1093,56 @@ pub trait RUStruct: RUField {
 
         unsafe { fun(self.to_struct_handle()) }
     }
+
+    /// Iterates over this struct followed by all of its super structs
+    fn parents_iter(&self) -> StructParentsIter {
+        StructParentsIter {
+            current: UStruct::from_ptr_safe(self.to_ptr()),
+        }
+    }
+
+    /// Iterates over the child properties declared directly on this struct
+    fn properties_iter(&self) -> PropertiesIter {
+        PropertiesIter {
+            current: FField::from_ptr_safe(self.get_child_properties().to_ptr()),
+        }
+    }
+
+    /// Checks whether this struct is `other` or one of its descendants
+    fn is_child_of(&self, other: &impl RUStruct) -> bool {
+        self.parents_iter()
+            .any(|parent| std::ptr::addr_eq(parent.to_ptr(), other.to_ptr()))
+    }
+}
+
+pub struct StructParentsIter {
+    current: Option<UStruct>,
+}
+
+impl Iterator for StructParentsIter {
+    type Item = UStruct;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let current = self.current?;
+        self.current = UStruct::from_ptr_safe(current.get_super_struct().to_ptr());
+
+        Some(current)
+    }
+}
+
+pub struct PropertiesIter {
+    current: Option<FField>,
+}
+
+impl Iterator for PropertiesIter {
+    type Item = FField;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let current = self.current?;
+        self.current = current.get_next();
+
+        Some(current)
+    }
 }
 
 impl UClass {
@@ -670,6 +1164,39 @@ impl UClass {
         objects.into_iter().map(|obj| obj.unsafe_cast()).collect()
     }
 
+    /// Same as [`UClass::get_objects_matching`], but casts lazily while iterating
+    ///
+    /// Objects are fetched once up front, but only cast when the iterator reaches them.
+    pub fn objects_matching_iter<T: StaticClass>(
+        &self,
+        allow_default: bool,
+    ) -> impl Iterator<Item = T> {
+        let objects = self.get_objects_matching_raw(allow_default);
+
+        objects.into_iter().filter_map(|obj| obj.cast())
+    }
+
+    /// Collects the objects that match `pred`
+    pub fn objects_matching_filtered<T: StaticClass>(
+        &self,
+        allow_default: bool,
+        pred: impl Fn(&T) -> bool,
+    ) -> Vec<T> {
+        self.objects_matching_iter(allow_default)
+            .filter(|obj| pred(obj))
+            .collect()
+    }
+
+    /// Returns the first object that matches `pred`, without casting the objects after it
+    pub fn first_object_matching_filtered<T: StaticClass>(
+        &self,
+        allow_default: bool,
+        pred: impl Fn(&T) -> bool,
+    ) -> Option<T> {
+        self.objects_matching_iter(allow_default)
+            .find(|obj| pred(obj))
+    }
+
     pub fn get_first_object_matching<T: StaticClass>(&self, allow_default: bool) -> Option<T> {
         let object = self.get_first_object_matching_raw(allow_default);
 
@@ -699,20 +1226,26 @@ impl UClass {
             return vec![];
         }
 
-        let mut result = Vec::with_capacity(size as _);
+        // Objects can be destroyed in between both calls, so only the amount of objects
+        // reported by the second call is used
+        let mut result = UeVec::with_capacity(size as _);
 
         unsafe {
-            result.set_len(size as _);
-
-            fun(
+            let written = fun(
                 self.to_handle(),
                 result.as_mut_ptr(),
                 size as u32,
                 allow_default,
             );
+
+            result.set_len(written.min(size) as _);
         }
 
-        result.into_iter().map(UObject::from_handle).collect()
+        result
+            .iter()
+            .copied()
+            .flat_map(UObject::from_handle_safe)
+            .collect()
     }
 
     fn get_first_object_matching_raw(&self, allow_default: bool) -> Option<UObject> {
@@ -762,6 +1295,74 @@ impl UFunction {
 
         unsafe { fun(self.to_handle(), flags) }
     }
+
+    /// Replaces the native implementation (`Func`) of the function, until the returned guard is dropped
+    ///
+    /// The hook is called with the object, the `FFrame` and the return value address, and can call
+    /// [`FunctionHookGuard::original`] to run the original implementation. Returns `None` if the
+    /// function pointer couldn't be found.
+    pub fn set_hook(
+        &self,
+        hook: unsafe extern "C" fn(*mut c_void, *mut c_void, *mut c_void),
+    ) -> Option<FunctionHookGuard> {
+        let field = self.native_function_field()?;
+        let original = unsafe { std::mem::replace(&mut *field, hook as *mut c_void) };
+
+        Some(FunctionHookGuard {
+            field,
+            original,
+            hook: hook as *mut c_void,
+        })
+    }
+
+    /// Finds the `Func` member, by looking for the pointer UEVR returns for it
+    ///
+    /// `Func` is the last member of UFunction, so only the part after the UObject members is searched,
+    /// up to the size of the object as reported by its class.
+    fn native_function_field(&self) -> Option<*mut *mut c_void> {
+        let native = self.get_native_function();
+        if self.is_invalid() || native.is_null() {
+            return None;
+        }
+
+        let size = usize::try_from(self.get_class()?.get_properties_size()).ok()?;
+        let base = self.to_ptr() as *mut *mut c_void;
+
+        (0x40 / 8..size / 8)
+            .map(|index| unsafe { base.add(index) })
+            .find(|field| unsafe { **field == native })
+    }
+}
+
+/// Restores the native implementation of a function hooked using [`UFunction::set_hook`] when dropped
+///
+/// Dropping writes into the UFunction, so the guard must be dropped before the function is destroyed,
+/// otherwise freed memory is written to.
+pub struct FunctionHookGuard {
+    field: *mut *mut c_void,
+    original: *mut c_void,
+    hook: *mut c_void,
+}
+
+// The guard only points into the UFunction, which isn't tied to a thread
+unsafe impl Send for FunctionHookGuard {}
+
+impl FunctionHookGuard {
+    /// Returns the native implementation that was replaced
+    pub fn original(&self) -> unsafe extern "C" fn(*mut c_void, *mut c_void, *mut c_void) {
+        unsafe { std::mem::transmute(self.original) }
+    }
+}
+
+impl Drop for FunctionHookGuard {
+    fn drop(&mut self) {
+        // Leave the pointer alone if something else replaced it after us
+        unsafe {
+            if *self.field == self.hook {
+                *self.field = self.original;
+            }
+        }
+    }
 }
 
 pub struct StructOpts {
@@ -935,6 +1536,17 @@ impl FEnumProperty {
     }
 }
 
+impl FSoftObjectProperty {
+    /// Reads the path stored in this property on `object`
+    pub fn get_value_from_object<'a>(&self, object: &'a impl RUObject) -> &'a FSoftObjectPath {
+        unsafe {
+            let data = object.to_ptr().byte_add(self.get_offset() as _);
+
+            FSoftObjectPath::from_soft_object_ptr(data as _)
+        }
+    }
+}
+
 impl FFieldClass {
     pub fn get_fname(&self) -> FName {
         let fun = Self::initialize().get_fname.unwrap();
@@ -955,33 +1567,124 @@ pub struct ConsoleObjectElement {
     unk2: [i32; 2],
 }
 
-// TODO: If there's no need to use TArray anywhere else, we can just instantly convert to a Vec and free the original memory
+const _: () = {
+    assert!(size_of::<ConsoleObjectElement>() == 32);
+    assert!(offset_of!(ConsoleObjectElement, key) == 0);
+    assert!(offset_of!(ConsoleObjectElement, value) == 16);
+};
+
+impl ConsoleObjectElement {
+    /// The name of the console object
+    pub fn key(&self) -> String {
+        if self.key.is_null() {
+            return "".to_string();
+        }
+
+        unsafe {
+            let len = (0..).take_while(|&i| *self.key.add(i) != 0).count();
+
+            String::from_utf16_lossy(std::slice::from_raw_parts(self.key, len))
+        }
+    }
+
+    pub fn value(&self) -> Option<IConsoleObject> {
+        IConsoleObject::from_ptr_safe(self.value as *mut c_void)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ConsoleObjectKind {
+    Command,
+    Variable,
+}
+
+/// Result of [`FConsoleManager::search`]
+#[derive(Clone, Debug)]
+pub struct ConsoleMatch {
+    pub name: String,
+    pub kind: ConsoleObjectKind,
+    /// The numeric value of a variable, `None` for commands
+    pub value: Option<String>,
+    /// Whether the variable may be a string variable, whose value can't be read
+    ///
+    /// UEVR doesn't expose the type of a variable, and string variables read as 0. So this is set for
+    /// every variable that reads as 0, `value` is only meaningful if it's a numeric variable.
+    pub may_be_string: bool,
+}
+
 impl FConsoleManager {
-    pub fn get_console_objects(&self) -> TArray<ConsoleObjectElement> {
+    /// Returns the names and objects of all registered console objects, skipping dead entries
+    pub fn get_console_objects(&self) -> Vec<(String, IConsoleObject)> {
+        let Some(objects) = self.console_objects_raw() else {
+            return vec![];
+        };
+
+        objects
+            .into_iter()
+            .filter(|element| !element.key.is_null())
+            .flat_map(|element| Some((element.key(), element.value()?)))
+            .collect()
+    }
+
+    /// Finds all console objects whose name contains `needle`, ignoring case
+    pub fn search(&self, needle: &str) -> Vec<ConsoleMatch> {
+        let needle = needle.to_lowercase();
+
+        self.get_console_objects()
+            .into_iter()
+            .filter(|(name, _)| name.to_lowercase().contains(&needle))
+            .map(|(name, object)| match object.as_command() {
+                Some(_) => ConsoleMatch {
+                    name,
+                    kind: ConsoleObjectKind::Command,
+                    value: None,
+                    may_be_string: false,
+                },
+                None => {
+                    let value = IConsoleVariable::from_ptr(object.to_ptr()).get_float();
+
+                    ConsoleMatch {
+                        name,
+                        kind: ConsoleObjectKind::Variable,
+                        value: Some(value.to_string()),
+                        may_be_string: value == 0.0,
+                    }
+                }
+            })
+            .collect()
+    }
+
+    /// Borrowed view of the engine's console object array, which is still owned by the engine
+    pub fn console_objects_raw(&self) -> Option<TArrayRef<'_, ConsoleObjectElement>> {
         let fun = Self::initialize().get_console_objects.unwrap();
+        let objects = unsafe { fun(self.to_handle()) } as *const TArray<ConsoleObjectElement>;
+
+        if objects.is_null() {
+            return None;
+        }
 
-        unsafe { (&*(fun(self.to_handle()) as *const TArray<ConsoleObjectElement>)).clone() }
+        unsafe { Some(TArrayRef::from_raw(objects)) }
     }
 
-    pub fn find_object(&self, name: impl AsRef<str>) -> IConsoleObject {
+    pub fn find_object(&self, name: impl AsRef<str>) -> Option<IConsoleObject> {
         let name = encode_wstr(name);
         let fun = Self::initialize().find_object.unwrap();
 
-        unsafe { IConsoleObject::from_handle(fun(self.to_handle(), name.as_ptr())) }
+        unsafe { IConsoleObject::from_handle_safe(fun(self.to_handle(), name.as_ptr())) }
     }
 
-    pub fn find_variable(&self, name: impl AsRef<str>) -> IConsoleVariable {
+    pub fn find_variable(&self, name: impl AsRef<str>) -> Option<IConsoleVariable> {
         let name = encode_wstr(name);
         let fun = Self::initialize().find_variable.unwrap();
 
-        unsafe { IConsoleVariable::from_handle(fun(self.to_handle(), name.as_ptr())) }
+        unsafe { IConsoleVariable::from_handle_safe(fun(self.to_handle(), name.as_ptr())) }
     }
 
-    pub fn find_command(&self, name: impl AsRef<str>) -> IConsoleCommand {
+    pub fn find_command(&self, name: impl AsRef<str>) -> Option<IConsoleCommand> {
         let name = encode_wstr(name);
         let fun = Self::initialize().find_command.unwrap();
 
-        unsafe { IConsoleCommand::from_handle(fun(self.to_handle(), name.as_ptr())) }
+        unsafe { IConsoleCommand::from_handle_safe(fun(self.to_handle(), name.as_ptr())) }
     }
 }
 
@@ -990,10 +1693,11 @@ pub trait RIConsoleObject: Ptr {
         self.to_ptr() as _
     }
 
-    fn as_command(&self) -> IConsoleCommand {
+    /// Converts the object to a command, returning `None` if it's a variable
+    fn as_command(&self) -> Option<IConsoleCommand> {
         let fun = IConsoleObject::initialize().as_command.unwrap();
 
-        unsafe { IConsoleCommand::from_handle(fun(self.to_iconsole_handle())) }
+        unsafe { IConsoleCommand::from_handle_safe(fun(self.to_iconsole_handle())) }
     }
 }
 
@@ -1013,6 +1717,44 @@ impl IConsoleVariable {
         unsafe { fun(self.to_handle(), value.as_ptr(), flags) }
     }
 
+    pub fn set_int(&self, value: i32) {
+        self.set_ex_int(value, None)
+    }
+
+    pub fn set_float(&self, value: f32) {
+        self.set_ex_float(value, None)
+    }
+
+    pub fn set_bool(&self, value: bool) {
+        self.set_ex_bool(value, None)
+    }
+
+    pub fn set_ex_int(&self, value: i32, flags: Option<u32>) {
+        self.set_ex_display(value, flags)
+    }
+
+    pub fn set_ex_float(&self, value: f32, flags: Option<u32>) {
+        self.set_ex_display(value, flags)
+    }
+
+    pub fn set_ex_bool(&self, value: bool, flags: Option<u32>) {
+        self.set_ex_int(value as i32, flags)
+    }
+
+    /// Formats the value into a stack buffer, so no allocation is needed when setting numbers
+    fn set_ex_display(&self, value: impl fmt::Display, flags: Option<u32>) {
+        let mut buffer = WideStackBuffer::<64>::new();
+
+        if fmt::Write::write_fmt(&mut buffer, format_args!("{value}")).is_err() {
+            return self.set_ex(value.to_string(), flags);
+        }
+
+        let flags = flags.unwrap_or(0x80000000);
+        let fun = Self::initialize().variable_set_ex.unwrap();
+
+        unsafe { fun(self.to_handle(), buffer.as_ptr(), flags) }
+    }
+
     pub fn get_int(&self) -> i32 {
         let fun = Self::initialize().variable_get_int.unwrap();
 
@@ -1024,7 +1766,381 @@ impl IConsoleVariable {
 
         unsafe { fun(self.to_handle()) }
     }
-}
+
+    /// Reads the value as a bool, any non-zero value is considered true
+    pub fn get_bool(&self) -> bool {
+        self.get_int() != 0
+    }
+}
+
+/// Console variable that keeps its handle around, so it doesn't have to be looked up every time
+///
+/// The handle is resolved again by name after [`CVar::invalidate`], or when it couldn't be resolved before.
+pub struct CVar {
+    name: String,
+    handle: Cell<Option<IConsoleVariable>>,
+}
+
+// The handle is only the address of an engine object, which doesn't belong to any thread
+unsafe impl Send for CVar {}
+
+impl CVar {
+    /// Looks up a console variable, returning `None` if it doesn't exist in this game
+    pub fn find(name: impl Into<String>) -> Option<CVar> {
+        let cvar = CVar {
+            name: name.into(),
+            handle: Cell::new(None),
+        };
+
+        cvar.handle()?;
+        Some(cvar)
+    }
+
+    /// Like [`CVar::find`], but logs a warning the first time a variable can't be found
+    pub fn find_or_log(name: impl Into<String>) -> Option<CVar> {
+        let name = name.into();
+        let cvar = Self::find(name.clone());
+
+        if cvar.is_none() && LOGGED_MISSING_CVARS.lock().unwrap().insert(name.clone()) {
+            warn!("Console variable {name} does not exist in this game");
+        }
+
+        cvar
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Drops the cached handle, so it's looked up again on the next access
+    pub fn invalidate(&self) {
+        self.handle.set(None);
+    }
+
+    /// Returns the cached handle, resolving it again if needed
+    pub fn handle(&self) -> Option<IConsoleVariable> {
+        if let Some(handle) = self.handle.get() {
+            return Some(handle);
+        }
+
+        let handle = API::get().get_console_manager().find_variable(&self.name);
+
+        self.handle.set(handle);
+        handle
+    }
+
+    pub fn get_int(&self) -> Option<i32> {
+        self.handle().map(|handle| handle.get_int())
+    }
+
+    pub fn get_float(&self) -> Option<f32> {
+        self.handle().map(|handle| handle.get_float())
+    }
+
+    pub fn get_bool(&self) -> Option<bool> {
+        self.handle().map(|handle| handle.get_bool())
+    }
+
+    /// Sets the value, returning false if the variable couldn't be resolved
+    pub fn set(&self, value: impl AsRef<str>) -> bool {
+        self.handle().map(|handle| handle.set(value)).is_some()
+    }
+
+    pub fn set_int(&self, value: i32) -> bool {
+        self.handle().map(|handle| handle.set_int(value)).is_some()
+    }
+
+    pub fn set_float(&self, value: f32) -> bool {
+        self.handle()
+            .map(|handle| handle.set_float(value))
+            .is_some()
+    }
+
+    pub fn set_bool(&self, value: bool) -> bool {
+        self.handle().map(|handle| handle.set_bool(value)).is_some()
+    }
+}
+
+type CVarChangeCallback = Box<dyn FnMut(&str, &str, &str) + Send>;
+
+/// Calls a callback with the name, old and new value whenever one of a set of console variables changes
+///
+/// Only numeric variables are supported. String values can't be read (see [`IConsoleVariable`]) and read as
+/// 0, so changes to a string variable are never reported. UEVR doesn't expose the type of a variable
+/// either, so string variables can't be rejected up front. Variables that don't exist yet are watched once
+/// they appear.
+pub struct CVarWatcher {
+    cvars: Vec<(String, Option<CVar>, Option<String>)>,
+    callback: Option<CVarChangeCallback>,
+}
+
+impl CVarWatcher {
+    pub fn new(names: &[&str]) -> Self {
+        let mut watcher = Self {
+            cvars: names
+                .iter()
+                .map(|name| (name.to_string(), None, None))
+                .collect(),
+            callback: None,
+        };
+
+        watcher.poll();
+        watcher
+    }
+
+    /// Sets the callback, which is called with the name, old and new value of a changed variable
+    pub fn on_change(mut self, callback: impl FnMut(&str, &str, &str) + Send + 'static) -> Self {
+        self.callback = Some(Box::new(callback));
+        self
+    }
+
+    /// Compares the current values against the previous poll, calling the callback for every change
+    pub fn poll(&mut self) {
+        for (name, cvar, value) in &mut self.cvars {
+            if cvar.is_none() {
+                *cvar = CVar::find(name.as_str());
+            }
+
+            let Some(current) = cvar
+                .as_ref()
+                .and_then(|cvar| cvar.get_float())
+                .map(|value| value.to_string())
+            else {
+                continue;
+            };
+
+            // Variables that just appeared are only snapshotted
+            let Some(previous) = value.replace(current.clone()) else {
+                continue;
+            };
+
+            if previous != current {
+                if let Some(callback) = &mut self.callback {
+                    callback(name, &previous, &current);
+                }
+            }
+        }
+    }
+
+    /// Hands the watcher over to the crate, which polls it before every engine tick
+    pub fn register(self) {
+        CVAR_WATCHERS.lock().unwrap().push(self);
+    }
+}
+
+/// Calls a callback with the address of a property whenever its bytes change
+///
+/// `prop_name` may be a path, see [`resolve_property_path`]. Only properties with a fixed size are
+/// supported (numbers, bools, names, enums, object pointers and structs), strings and arrays aren't.
+/// A struct is compared as a whole, so a change to any of its fields fires the callback once.
+pub struct PropertyWatcher {
+    obj: UObjectWeak,
+    prop_name: String,
+    prev_bytes: Vec<u8>,
+    resolved: Option<ResolvedProperty>,
+    callback: Box<dyn Fn(*mut c_void) + Send>,
+}
+
+/// Where a watched property was found, so polling doesn't have to look it up by name every tick
+struct ResolvedProperty {
+    hops: Vec<PropertyHop>,
+    offset: usize,
+    size: usize,
+}
+
+// Watchers are only polled from the game thread
+unsafe impl Send for PropertyWatcher {}
+
+impl PropertyWatcher {
+    /// Starts watching a property of `obj`
+    ///
+    /// The object is tracked using a [`UObjectWeak`], so UObjectHook has to be active, see
+    /// [`object_hook::activate`]. Otherwise the watcher is dropped on its first poll.
+    pub fn new(
+        obj: UObject,
+        prop_name: &str,
+        callback: impl Fn(*mut c_void) + Send + 'static,
+    ) -> Self {
+        let mut watcher = Self {
+            obj: UObjectWeak::new(obj),
+            prop_name: prop_name.to_string(),
+            prev_bytes: vec![],
+            resolved: None,
+            callback: Box::new(callback),
+        };
+
+        if let Some((data, size)) = watcher.locate() {
+            watcher.prev_bytes = unsafe { std::slice::from_raw_parts(data, size).to_vec() };
+        }
+
+        watcher
+    }
+
+    /// Compares the property against the previous poll, calling the callback if it changed
+    ///
+    /// Returns `false` once the object is gone.
+    pub fn poll(&mut self) -> bool {
+        if self.obj.get().is_none() {
+            return false;
+        }
+
+        let Some((data, size)) = self.locate() else {
+            return true;
+        };
+
+        let bytes = unsafe { std::slice::from_raw_parts(data, size) };
+
+        if bytes != self.prev_bytes.as_slice() {
+            self.prev_bytes = bytes.to_vec();
+            (self.callback)(data as _);
+        }
+
+        true
+    }
+
+    /// Hands the watcher over to the crate, which polls it before every engine tick until the object is destroyed
+    pub fn register(self) {
+        PROPERTY_WATCHERS.lock().unwrap().push(self);
+    }
+
+    /// Returns the address and size of the property value
+    ///
+    /// The path is only resolved again when an object along it was replaced.
+    fn locate(&mut self) -> Option<(*mut u8, usize)> {
+        let obj = self.obj.get()?;
+
+        if let Some(resolved) = &self.resolved {
+            if let Some(base) = follow_property_hops(obj.to_ptr(), &resolved.hops) {
+                return unsafe { Some(((base as *mut u8).add(resolved.offset), resolved.size)) };
+            }
+        }
+
+        self.resolved = None;
+
+        let (base, property, hops) = resolve_property_hops(&obj, &self.prop_name)?;
+        let offset = property.get_offset() as usize;
+        let size = property_size(&property)?;
+
+        self.resolved = Some(ResolvedProperty { hops, offset, size });
+
+        unsafe { Some(((base as *mut u8).add(offset), size)) }
+    }
+}
+
+/// Size of a property value, for the property types that have a fixed size
+fn property_size(property: &FProperty) -> Option<usize> {
+    let size = match property.get_class().get_name().as_str() {
+        "BoolProperty" | "ByteProperty" | "Int8Property" => 1,
+        "Int16Property" | "UInt16Property" => 2,
+        "IntProperty" | "UInt32Property" | "FloatProperty" => 4,
+        "Int64Property" | "UInt64Property" | "DoubleProperty" | "NameProperty" => 8,
+        "ObjectProperty" | "ObjectPtrProperty" | "ClassProperty" | "WeakObjectProperty" => 8,
+        "StructProperty" => {
+            let inner = FStructProperty::from_ptr(property.to_ptr()).get_struct();
+
+            usize::try_from(inner.get_struct_size()).ok()?
+        }
+        "EnumProperty" => {
+            let underlying = FEnumProperty::from_ptr(property.to_ptr()).get_underlying_prop();
+
+            return property_size(&FProperty::from_ptr_safe(underlying.to_ptr())?);
+        }
+        _ => return None,
+    };
+
+    Some(size)
+}
+
+/// Returns the amount of engine ticks since the plugin was loaded
+pub fn get_frame_number() -> u64 {
+    FRAME_NUMBER.load(Ordering::Relaxed)
+}
+
+/// Returns the sum of all engine tick deltas since the plugin was loaded, in seconds
+pub fn get_elapsed_game_time() -> f32 {
+    f64::from_bits(ELAPSED_GAME_TIME.load(Ordering::Relaxed)) as f32
+}
+
+/// Advances the frame counter and game time, only the game thread writes to them
+pub(crate) fn advance_game_clock(delta: f32) {
+    let elapsed = f64::from_bits(ELAPSED_GAME_TIME.load(Ordering::Relaxed)) + delta as f64;
+
+    ELAPSED_GAME_TIME.store(elapsed.to_bits(), Ordering::Relaxed);
+    FRAME_NUMBER.fetch_add(1, Ordering::Relaxed);
+}
+
+/// Queues a closure to run on the game thread, before the next engine tick
+pub fn run_on_game_thread(f: impl FnOnce() + Send + 'static) {
+    GAME_THREAD_QUEUE.lock().unwrap().push(Box::new(f));
+}
+
+pub(crate) fn run_game_thread_queue() {
+    // Closures may queue more closures, which will run during the next tick
+    let queue = std::mem::take(&mut *GAME_THREAD_QUEUE.lock().unwrap());
+
+    for f in queue {
+        f();
+    }
+}
+
+pub(crate) fn poll_property_watchers() {
+    // Callbacks may register new watchers, so the lock isn't held while polling
+    let mut watchers = std::mem::take(&mut *PROPERTY_WATCHERS.lock().unwrap());
+
+    watchers.retain_mut(PropertyWatcher::poll);
+
+    let mut registered = PROPERTY_WATCHERS.lock().unwrap();
+    watchers.append(&mut registered);
+    *registered = watchers;
+}
+
+pub(crate) fn poll_cvar_watchers() {
+    // Callbacks may register new watchers, so the lock isn't held while polling
+    let mut watchers = std::mem::take(&mut *CVAR_WATCHERS.lock().unwrap());
+
+    for watcher in &mut watchers {
+        watcher.poll();
+    }
+
+    let mut registered = CVAR_WATCHERS.lock().unwrap();
+    watchers.append(&mut registered);
+    *registered = watchers;
+}
+
+/// Null-terminated wide string with a fixed capacity, used to pass short strings without allocating
+struct WideStackBuffer<const N: usize> {
+    data: [u16; N],
+    len: usize,
+}
+
+impl<const N: usize> WideStackBuffer<N> {
+    fn new() -> Self {
+        Self {
+            data: [0; N],
+            len: 0,
+        }
+    }
+
+    fn as_ptr(&self) -> *const u16 {
+        self.data.as_ptr()
+    }
+}
+
+impl<const N: usize> fmt::Write for WideStackBuffer<N> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        for c in s.encode_utf16() {
+            // Always keep room for the null terminator
+            if self.len + 1 >= N {
+                return Err(fmt::Error);
+            }
+
+            self.data[self.len] = c;
+            self.len += 1;
+        }
+
+        Ok(())
+    }
+}
 
 impl IConsoleCommand {
     pub fn execute(&self, args: impl AsRef<str>) {
@@ -1041,6 +2157,48 @@ impl UEngine {
     }
 }
 
+impl UGameEngine {
+    /// The world of the game viewport, which is the world that is currently being played
+    pub fn get_world(&self) -> Option<UWorld> {
+        let viewport = self.read_object_property("GameViewport")?;
+
+        viewport.read_object_property("World")?.cast()
+    }
+}
+
+impl UWorld {
+    /// Whether the game is paused, using `GameplayStatics::IsGamePaused`
+    pub fn is_paused(&self) -> Option<bool> {
+        #[repr(C)]
+        struct Params {
+            world_context_object: UEVR_UObjectHandle,
+            return_value: bool,
+        }
+
+        static FUNCTION: AtomicPtr<c_void> = AtomicPtr::new(null_mut());
+        static STATICS: AtomicPtr<c_void> = AtomicPtr::new(null_mut());
+
+        let api = API::get();
+        let function = find_object_cached(&FUNCTION, || {
+            api.find_uobject::<UFunction>("Function /Script/Engine.GameplayStatics.IsGamePaused")
+        })?;
+        let statics = find_object_cached(&STATICS, || {
+            api.find_uobject::<UClass>("Class /Script/Engine.GameplayStatics")
+                .map(|class| class.get_class_default_object())
+        })?;
+
+        let mut params = Params {
+            world_context_object: self.to_object_handle(),
+            return_value: false,
+        };
+
+        statics.process_event(function, &mut params as *mut Params as _);
+
+        Some(params.return_value)
+    }
+}
+
+#[repr(C)]
 #[derive(Clone, Copy)]
 pub struct FUObjectItem {
     pub object: UEVR_UObjectHandle,
@@ -1049,6 +2207,152 @@ pub struct FUObjectItem {
     pub serial_number: i32,
 }
 
+const _: () = {
+    assert!(size_of::<FUObjectItem>() == 24);
+    assert!(offset_of!(FUObjectItem, object) == 0);
+    assert!(offset_of!(FUObjectItem, flags) == 8);
+    assert!(offset_of!(FUObjectItem, cluster_index) == 12);
+    assert!(offset_of!(FUObjectItem, serial_number) == 16);
+};
+
+impl FUObjectItem {
+    /// The object in this slot, if there is one
+    pub fn get_object(&self) -> Option<UObject> {
+        UObject::from_handle_safe(self.object)
+    }
+
+    /// `EInternalObjectFlags::PendingKill` (UE4) and `EInternalObjectFlags::Garbage` (UE5)
+    const PENDING_KILL_FLAGS: i32 = (1 << 29) | (1 << 21);
+
+    /// Whether the object in this slot is about to be destroyed
+    pub fn is_pending_kill(&self) -> bool {
+        self.flags & Self::PENDING_KILL_FLAGS != 0
+    }
+}
+
+/// Memory layout of an engine FWeakObjectPtr
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FWeakObjectPtr {
+    pub object_index: i32,
+    pub object_serial_number: i32,
+}
+
+impl FWeakObjectPtr {
+    /// Whether this pointer was explicitly set to null
+    pub fn is_null(&self) -> bool {
+        self.object_index < 0 || self.object_serial_number == 0
+    }
+
+    /// Returns the object, if it still exists and its slot hasn't been reused by another object
+    pub fn resolve(&self, array: &FUObjectArray) -> Option<UObject> {
+        if self.is_null() || self.object_index >= array.get_object_count() {
+            return None;
+        }
+
+        let item = array.get_item(self.object_index);
+        if item.serial_number != self.object_serial_number || item.is_pending_kill() {
+            return None;
+        }
+
+        item.get_object()
+    }
+}
+
+/// Object reference that doesn't keep the object alive, checked using UEVR's object tracking
+///
+/// The class of the object is remembered as well, so a different kind of object allocated at the same
+/// address isn't mistaken for the original one.
+#[derive(Clone, Copy)]
+pub struct UObjectWeak {
+    object: UObject,
+    class: *mut c_void,
+}
+
+impl UObjectWeak {
+    /// Starts tracking `object`
+    ///
+    /// Relies on UObjectHook to know which objects exist. It isn't activated here, as that hooks every
+    /// object construction and destruction; call [`object_hook::activate`] before using weak pointers,
+    /// otherwise [`UObjectWeak::get`] always returns `None`.
+    pub fn new(object: UObject) -> Self {
+        Self {
+            object,
+            class: object
+                .get_class()
+                .map_or(null_mut(), |class| class.to_ptr()),
+        }
+    }
+
+    /// Returns the object if it still exists
+    pub fn get(&self) -> Option<UObject> {
+        if self.object.is_invalid() || !object_hook::exists(self.object) {
+            return None;
+        }
+
+        let class = self.object.get_class()?;
+
+        std::ptr::eq(class.to_ptr(), self.class).then_some(self.object)
+    }
+}
+
+/// Memory layout of an engine FSoftObjectPath (UE4)
+///
+/// UE 5.1 and newer split the asset path into a package and asset name, which is not supported.
+#[repr(C)]
+pub struct FSoftObjectPath {
+    asset_path_name: u64,
+    sub_path: FString,
+}
+
+impl FSoftObjectPath {
+    /// Offset of the path in a soft object pointer, which is preceded by a weak pointer and a tag
+    const SOFT_OBJECT_PTR_OFFSET: usize = 16;
+
+    /// # Safety
+    ///
+    /// `ptr` must point to a valid FSoftObjectPath that outlives `'a`
+    pub unsafe fn from_ptr<'a>(ptr: *const c_void) -> &'a Self {
+        &*(ptr as *const Self)
+    }
+
+    /// # Safety
+    ///
+    /// `ptr` must point to a valid FSoftObjectPtr (e.g. `TSoftObjectPtr`) that outlives `'a`
+    pub unsafe fn from_soft_object_ptr<'a>(ptr: *const c_void) -> &'a Self {
+        Self::from_ptr(ptr.byte_add(Self::SOFT_OBJECT_PTR_OFFSET))
+    }
+
+    /// The path of the asset, e.g. `/Game/Weapons/Scope.Scope`
+    pub fn asset_path(&self) -> String {
+        FName::from_ptr(&self.asset_path_name as *const u64 as *mut c_void).to_string()
+    }
+
+    /// The path of the subobject within the asset, if any
+    pub fn sub_path(&self) -> String {
+        self.sub_path.to_string()
+    }
+
+    /// Looks the object up by its full name, returning `None` if it isn't loaded
+    ///
+    /// The path doesn't store the class of the object, so its name has to be given, e.g. `StaticMesh`.
+    pub fn try_resolve(&self, class_name: &str) -> Option<UObject> {
+        let asset_path = self.asset_path();
+        if asset_path.is_empty() || asset_path == "None" {
+            return None;
+        }
+
+        let sub_path = self.sub_path();
+        let name = if sub_path.is_empty() {
+            format!("{class_name} {asset_path}")
+        } else {
+            format!("{class_name} {asset_path}:{sub_path}")
+        };
+
+        API::get().find_uobject(name)
+    }
+}
+
 impl FUObjectArray {
     pub fn get() -> FUObjectArray {
         API::get().get_uobject_array()
@@ -1101,94 +2405,2046 @@ impl FUObjectArray {
 
         unsafe { &*(fun(self.to_handle(), index) as *const FUObjectItem) }
     }
-}
 
-impl FRHITexture2D {
-    pub fn get_native_resource(&self) -> *mut c_void {
-        let fun = Self::initialize().get_native_resource.unwrap();
+    /// Iterates over all objects in the array, skipping slots that don't hold an object
+    pub fn iter(&self) -> ObjectIter {
+        ObjectIter {
+            array: *self,
+            index: 0,
+        }
+    }
 
-        unsafe { fun(self.to_handle()) }
+    /// Iterates over all slots in the array, including the ones that don't hold an object
+    pub fn iter_items(&self) -> ObjectItemIter<'_> {
+        ObjectItemIter {
+            array: self,
+            index: 0,
+        }
     }
-}
 
-impl MotionControllerState {
-    pub fn set_rotation_offset(&self, offset: *const UEVR_Quaternionf) {
-        let fun = Self::initialize().set_rotation_offset.unwrap();
+    /// Walks the object array memory directly, instead of making an FFI call for every item
+    ///
+    /// Handles both the inlined and (chunked) pointer array layouts. The walk starts at
+    /// [`FUObjectArray::get_objects_ptr`], the address of the object storage within the array. The object
+    /// count is only read once, so objects added while walking are not included.
+    ///
+    /// # Safety
+    ///
+    /// The layout reported by UEVR must be correct, and the array must not be resized while walking it.
+    pub unsafe fn raw_items(&self) -> RawItemWalker<'_> {
+        RawItemWalker {
+            objects: self.get_objects_ptr() as *const u8,
+            is_chunked: Self::is_chunked(),
+            is_inlined: Self::is_inlined(),
+            item_distance: Self::get_item_distance() as _,
+            index: 0,
+            count: self.get_object_count().max(0) as _,
+            _array: PhantomData,
+        }
+    }
 
-        unsafe { fun(self.to_handle(), offset) }
+    /// Iterates over all objects in the array that are an instance of `T`
+    pub fn filter_by_class<T: StaticClass>(&self) -> impl Iterator<Item = T> {
+        self.iter().flat_map(|object| object.cast::<T>())
     }
 
-    pub fn set_location_offset(&self, offset: *const UEVR_Vector3f) {
-        let fun = Self::initialize().set_location_offset.unwrap();
+    /// Returns the first object in the array that matches `predicate`
+    pub fn find_first(&self, predicate: impl Fn(UObject) -> bool) -> Option<UObject> {
+        self.iter().find(|object| predicate(*object))
+    }
 
-        unsafe { fun(self.to_handle(), offset) }
+    /// Counts the objects in the array that are an instance of `c`
+    pub fn count_by_class(&self, c: UClass) -> usize {
+        self.iter().filter(|object| object.is_a(c)).count()
     }
 
-    pub fn set_hand(&self, hand: u32) {
-        let fun = Self::initialize().set_hand.unwrap();
+    /// Collects the objects of class `class` by walking the array directly, without using the UObjectHook
+    ///
+    /// If `include_derived` is set, instances of subclasses are included as well.
+    /// At most `limit` objects are returned, if given.
+    pub fn find_objects_of_class(
+        &self,
+        class: UClass,
+        include_derived: bool,
+        limit: Option<usize>,
+    ) -> Vec<UObject> {
+        let limit = limit.unwrap_or(usize::MAX);
+        let mut result = vec![];
+
+        for (_, item) in self.iter_items() {
+            if result.len() >= limit {
+                break;
+            }
 
-        unsafe { fun(self.to_handle(), hand) }
-    }
+            if item.is_pending_kill() {
+                continue;
+            }
 
-    pub fn set_permanent(&self, permanent: bool) {
-        let fun = Self::initialize().set_permanent.unwrap();
+            let Some(object) = UObject::from_handle_safe(item.object) else {
+                continue;
+            };
 
-        unsafe { fun(self.to_handle(), permanent) }
+            let Some(object_class) = object.get_class() else {
+                continue;
+            };
+
+            let matches = if include_derived {
+                object_class.is_child_of(&class)
+            } else {
+                std::ptr::addr_eq(object_class.to_ptr(), class.to_ptr())
+            };
+
+            if matches {
+                result.push(object);
+            }
+        }
+
+        result
     }
 }
 
-pub struct TArray<T> {
-    data: *mut T,
-    count: i32,
-    capacity: i32,
+/// Iterator over all live objects in a [`FUObjectArray`], see [`FUObjectArray::iter`]
+pub struct ObjectIter {
+    array: FUObjectArray,
+    index: i32,
 }
 
-impl<T> TArray<T> {
-    pub fn begin(&self) -> *const T {
-        self.data
-    }
+impl Iterator for ObjectIter {
+    type Item = UObject;
 
-    pub fn begin_mut(&mut self) -> *mut T {
-        self.data
-    }
+    fn next(&mut self) -> Option<Self::Item> {
+        // The object count is re-read every time, since objects can be added while iterating
+        while self.index < self.array.get_object_count() {
+            let item = self.array.get_item(self.index);
+            self.index += 1;
 
-    pub fn end(&self) -> *const T {
-        if self.data.is_null() {
-            null()
-        } else {
-            unsafe { self.data.byte_add(self.count as _) }
+            if let Some(object) = UObject::from_handle_safe(item.object) {
+                return Some(object);
+            }
         }
-    }
 
-    pub fn end_mut(&mut self) -> *mut T {
-        self.end() as _
-    }
-
-    pub fn empty(&self) -> bool {
-        self.count == 0 || self.data.is_null()
+        None
     }
+}
 
-    pub unsafe fn to_vec(self) -> Vec<T> {
-        Vec::from_raw_parts(self.data, self.count as _, self.capacity as _)
-    }
+/// Iterator over all slots in a [`FUObjectArray`], see [`FUObjectArray::iter_items`]
+pub struct ObjectItemIter<'a> {
+    array: &'a FUObjectArray,
+    index: i32,
 }
 
-impl<T> Clone for TArray<T> {
-    fn clone(&self) -> Self {
-        TArray {
-            data: self.data,
-            capacity: self.capacity,
-            count: self.count,
+impl<'a> Iterator for ObjectItemIter<'a> {
+    type Item = (i32, &'a FUObjectItem);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.index >= self.array.get_object_count() {
+            return None;
         }
+
+        let index = self.index;
+        self.index += 1;
+
+        Some((index, self.array.get_item(index)))
     }
 }
 
-impl<T> Drop for TArray<T> {
-    fn drop(&mut self) {
-        if !self.data.is_null() {
-            unsafe {
-                FMalloc::get().free(self.data as _);
+/// Direct memory walker over a [`FUObjectArray`], see [`FUObjectArray::raw_items`]
+pub struct RawItemWalker<'a> {
+    objects: *const u8,
+    is_chunked: bool,
+    is_inlined: bool,
+    item_distance: usize,
+    index: usize,
+    count: usize,
+    _array: PhantomData<&'a FUObjectArray>,
+}
+
+impl RawItemWalker<'_> {
+    const OBJECTS_PER_CHUNK: usize = 64 * 1024;
+
+    unsafe fn item_ptr(&self, index: usize) -> *const FUObjectItem {
+        if self.is_inlined {
+            return self.objects.byte_add(self.item_distance * index) as _;
+        }
+
+        if self.is_chunked {
+            let chunks = *(self.objects as *const *const *const u8);
+            if chunks.is_null() {
+                return null();
+            }
+
+            let chunk = *chunks.add(index / Self::OBJECTS_PER_CHUNK);
+            if chunk.is_null() {
+                return null();
+            }
+
+            return chunk.byte_add(self.item_distance * (index % Self::OBJECTS_PER_CHUNK)) as _;
+        }
+
+        let items = *(self.objects as *const *const u8);
+        if items.is_null() {
+            return null();
+        }
+
+        items.byte_add(self.item_distance * index) as _
+    }
+}
+
+impl<'a> Iterator for RawItemWalker<'a> {
+    type Item = (i32, &'a FUObjectItem);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.item_distance == 0 || self.index >= self.count {
+            return None;
+        }
+
+        let index = self.index;
+        let item = unsafe { self.item_ptr(index).as_ref()? };
+        self.index += 1;
+
+        Some((index as _, item))
+    }
+}
+
+impl FRHITexture2D {
+    pub fn get_native_resource(&self) -> *mut c_void {
+        let fun = Self::initialize().get_native_resource.unwrap();
+
+        unsafe { fun(self.to_handle()) }
+    }
+
+    /// Returns the width and height of the texture, read from the native D3D11/D3D12 resource
+    pub fn get_size(&self) -> Option<(u32, u32)> {
+        let (width, height, _) = self.get_desc()?;
+
+        Some((width, height))
+    }
+
+    /// Returns the width of the texture, or 0 if the native resource can't be queried
+    pub fn get_width(&self) -> u32 {
+        self.get_desc().map_or(0, |(width, _, _)| width)
+    }
+
+    /// Returns the height of the texture, or 0 if the native resource can't be queried
+    pub fn get_height(&self) -> u32 {
+        self.get_desc().map_or(0, |(_, height, _)| height)
+    }
+
+    /// Returns the `DXGI_FORMAT` of the texture, or 0 (`DXGI_FORMAT_UNKNOWN`) if the native resource can't be queried
+    pub fn get_pixel_format(&self) -> u32 {
+        self.get_desc().map_or(0, |(_, _, format)| format)
+    }
+
+    /// UEVR doesn't expose the texture description, so it's read from the native resource instead
+    fn get_desc(&self) -> Option<(u32, u32, u32)> {
+        let resource = self.get_native_resource();
+        if resource.is_null() {
+            return None;
+        }
+
+        unsafe {
+            match (*API::get().param().renderer).renderer_type as u32 {
+                UEVR_RENDERER_D3D11 => {
+                    let texture = ID3D11Texture2D::from_raw_borrowed(&resource)?;
+                    let mut desc = zeroed();
+                    texture.GetDesc(&mut desc);
+
+                    Some((desc.Width, desc.Height, desc.Format.0 as _))
+                }
+                UEVR_RENDERER_D3D12 => {
+                    let desc = ID3D12Resource::from_raw_borrowed(&resource)?.GetDesc();
+
+                    Some((desc.Width as _, desc.Height, desc.Format.0 as _))
+                }
+                _ => None,
             }
         }
     }
 }
+
+impl PartialEq for FRHITexture2D {
+    fn eq(&self, other: &Self) -> bool {
+        std::ptr::addr_eq(self.to_ptr(), other.to_ptr())
+    }
+}
+
+impl Eq for FRHITexture2D {}
+
+/// Values last written to a [`MotionControllerState`]
+///
+/// UEVR only exposes setters for these, so they are tracked on our side to be able to read them back.
+#[derive(Clone, Copy, Debug)]
+struct MotionControllerValues {
+    rotation_offset: UEVR_Quaternionf,
+    location_offset: UEVR_Vector3f,
+    hand: u32,
+    permanent: bool,
+}
+
+impl Default for MotionControllerValues {
+    // Matches the defaults UEVR uses for new states
+    fn default() -> Self {
+        Self {
+            rotation_offset: UEVR_Quaternionf {
+                w: 1.0,
+                x: 0.0,
+                y: 0.0,
+                z: 0.0,
+            },
+            location_offset: UEVR_Vector3f {
+                x: 0.0,
+                y: 0.0,
+                z: 0.0,
+            },
+            hand: 1,
+            permanent: false,
+        }
+    }
+}
+
+static MOTION_CONTROLLER_VALUES: LazyLock<Mutex<HashMap<usize, MotionControllerValues>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+impl MotionControllerState {
+    #[allow(clippy::not_unsafe_ptr_arg_deref)]
+    pub fn set_rotation_offset(&self, offset: *const UEVR_Quaternionf) {
+        let fun = Self::initialize().set_rotation_offset.unwrap();
+
+        if let Some(offset) = unsafe { offset.as_ref() } {
+            self.update_values(|values| values.rotation_offset = *offset);
+        }
+
+        unsafe { fun(self.to_handle(), offset) }
+    }
+
+    #[allow(clippy::not_unsafe_ptr_arg_deref)]
+    pub fn set_location_offset(&self, offset: *const UEVR_Vector3f) {
+        let fun = Self::initialize().set_location_offset.unwrap();
+
+        if let Some(offset) = unsafe { offset.as_ref() } {
+            self.update_values(|values| values.location_offset = *offset);
+        }
+
+        unsafe { fun(self.to_handle(), offset) }
+    }
+
+    pub fn set_hand(&self, hand: u32) {
+        let fun = Self::initialize().set_hand.unwrap();
+        self.update_values(|values| values.hand = hand);
+
+        unsafe { fun(self.to_handle(), hand) }
+    }
+
+    pub fn set_permanent(&self, permanent: bool) {
+        let fun = Self::initialize().set_permanent.unwrap();
+        self.update_values(|values| values.permanent = permanent);
+
+        unsafe { fun(self.to_handle(), permanent) }
+    }
+
+    pub fn builder(&self) -> MotionControllerStateBuilder {
+        MotionControllerStateBuilder::new(*self)
+    }
+
+    /// Returns the rotation offset last set by this plugin, or the UEVR default if it never set one
+    ///
+    /// UEVR can't read the state back, so changes made by UEVR or other plugins aren't seen.
+    pub fn get_rotation_offset(&self) -> UEVR_Quaternionf {
+        self.values().rotation_offset
+    }
+
+    /// Returns the location offset last set by this plugin, or the UEVR default if it never set one
+    pub fn get_location_offset(&self) -> UEVR_Vector3f {
+        self.values().location_offset
+    }
+
+    /// Returns the hand last set by this plugin, or the UEVR default if it never set one
+    pub fn get_hand(&self) -> u32 {
+        self.values().hand
+    }
+
+    /// Returns whether the state was last marked as permanent by this plugin
+    pub fn is_permanent(&self) -> bool {
+        self.values().permanent
+    }
+
+    /// Forgets the values tracked for this state, used when the state is removed
+    pub(crate) fn forget_values(&self) {
+        MOTION_CONTROLLER_VALUES
+            .lock()
+            .unwrap()
+            .remove(&(self.to_ptr() as usize));
+    }
+
+    /// Forgets the values tracked for all states
+    pub(crate) fn forget_all_values() {
+        MOTION_CONTROLLER_VALUES.lock().unwrap().clear();
+    }
+
+    fn values(&self) -> MotionControllerValues {
+        self.tracked_values().unwrap_or_default()
+    }
+
+    /// Values set by this plugin, `None` if it never set any
+    fn tracked_values(&self) -> Option<MotionControllerValues> {
+        MOTION_CONTROLLER_VALUES
+            .lock()
+            .unwrap()
+            .get(&(self.to_ptr() as usize))
+            .copied()
+    }
+
+    fn update_values(&self, update: impl FnOnce(&mut MotionControllerValues)) {
+        let mut values = MOTION_CONTROLLER_VALUES.lock().unwrap();
+
+        update(values.entry(self.to_ptr() as usize).or_default());
+    }
+}
+
+impl fmt::Debug for MotionControllerState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MotionControllerState")
+            .field("handle", &self.to_ptr())
+            .field("set_by_plugin", &self.tracked_values())
+            .finish()
+    }
+}
+
+/// Fluent helper to configure a [`MotionControllerState`], see [`MotionControllerState::builder`]
+///
+/// Only the values that were configured are applied.
+pub struct MotionControllerStateBuilder {
+    state: MotionControllerState,
+    hand: Option<u32>,
+    permanent: Option<bool>,
+    rotation_offset: Option<UEVR_Quaternionf>,
+    location_offset: Option<UEVR_Vector3f>,
+}
+
+impl MotionControllerStateBuilder {
+    pub fn new(state: MotionControllerState) -> Self {
+        Self {
+            state,
+            hand: None,
+            permanent: None,
+            rotation_offset: None,
+            location_offset: None,
+        }
+    }
+
+    pub fn hand(mut self, hand: u32) -> Self {
+        self.hand = Some(hand);
+        self
+    }
+
+    pub fn permanent(mut self, v: bool) -> Self {
+        self.permanent = Some(v);
+        self
+    }
+
+    pub fn rotation_offset(mut self, q: UEVR_Quaternionf) -> Self {
+        self.rotation_offset = Some(q);
+        self
+    }
+
+    pub fn location_offset(mut self, v: UEVR_Vector3f) -> Self {
+        self.location_offset = Some(v);
+        self
+    }
+
+    pub fn apply(self) {
+        if let Some(hand) = self.hand {
+            self.state.set_hand(hand);
+        }
+
+        if let Some(permanent) = self.permanent {
+            self.state.set_permanent(permanent);
+        }
+
+        if let Some(rotation_offset) = &self.rotation_offset {
+            self.state.set_rotation_offset(rotation_offset);
+        }
+
+        if let Some(location_offset) = &self.location_offset {
+            self.state.set_location_offset(location_offset);
+        }
+    }
+}
+
+/// Memory layout of an engine TArray
+///
+/// This type never frees its memory, use [`TArrayRef`] for arrays owned by the engine
+/// and [`TArrayOwned`] for arrays that have been allocated for us.
+#[repr(C)]
+pub struct TArray<T> {
+    data: *mut T,
+    count: i32,
+    capacity: i32,
+}
+
+impl<T> TArray<T> {
+    pub fn begin(&self) -> *const T {
+        self.data
+    }
+
+    pub fn begin_mut(&mut self) -> *mut T {
+        self.data
+    }
+
+    pub fn end(&self) -> *const T {
+        if self.data.is_null() {
+            null()
+        } else {
+            unsafe { self.data.add(self.len()) }
+        }
+    }
+
+    pub fn end_mut(&mut self) -> *mut T {
+        self.end() as _
+    }
+
+    pub fn empty(&self) -> bool {
+        self.count == 0 || self.data.is_null()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.empty()
+    }
+
+    pub fn len(&self) -> usize {
+        if self.data.is_null() {
+            0
+        } else {
+            self.count.max(0) as _
+        }
+    }
+
+    pub fn get(&self, index: usize) -> Option<&T> {
+        self.as_slice().get(index)
+    }
+
+    pub fn as_slice(&self) -> &[T] {
+        if self.empty() {
+            return &[];
+        }
+
+        unsafe { std::slice::from_raw_parts(self.data, self.len()) }
+    }
+
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        if self.empty() {
+            return &mut [];
+        }
+
+        unsafe { std::slice::from_raw_parts_mut(self.data, self.len()) }
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, T> {
+        self.as_slice().iter()
+    }
+
+    pub fn iter_mut(&mut self) -> std::slice::IterMut<'_, T> {
+        self.as_mut_slice().iter_mut()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a TArray<T> {
+    type Item = &'a T;
+    type IntoIter = std::slice::Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// Borrowed view of a TArray owned by the engine
+#[derive(Clone, Copy)]
+pub struct TArrayRef<'a, T> {
+    array: &'a TArray<T>,
+}
+
+impl<'a, T> TArrayRef<'a, T> {
+    /// # Safety
+    ///
+    /// `ptr` must point to a valid TArray that outlives `'a`
+    pub unsafe fn from_raw(ptr: *const TArray<T>) -> Self {
+        Self { array: &*ptr }
+    }
+
+    /// Copies the elements into a Vec
+    pub fn to_vec(&self) -> Vec<T>
+    where
+        T: Clone,
+    {
+        self.array.as_slice().to_vec()
+    }
+}
+
+impl<'a, T> IntoIterator for TArrayRef<'a, T> {
+    type Item = &'a T;
+    type IntoIter = std::slice::Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.array.iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &TArrayRef<'a, T> {
+    type Item = &'a T;
+    type IntoIter = std::slice::Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.array.iter()
+    }
+}
+
+impl<T> Deref for TArrayRef<'_, T> {
+    type Target = TArray<T>;
+
+    fn deref(&self) -> &Self::Target {
+        self.array
+    }
+}
+
+/// Box whose memory is allocated using FMalloc, so it can be handed over to the engine
+pub struct UeBox<T> {
+    ptr: NonNull<T>,
+    _marker: PhantomData<T>,
+}
+
+impl<T> UeBox<T> {
+    pub fn new(value: T) -> Self {
+        // FMalloc may hand out null for empty allocations, so always request at least a byte
+        let size = u32::try_from(size_of::<T>().max(1)).expect("UeBox value is too large");
+        let data = unsafe { FMalloc::get().malloc(size, std::mem::align_of::<T>() as _) };
+
+        let ptr = NonNull::new(data as *mut T)
+            .unwrap_or_else(|| panic!("FMalloc failed to allocate {size} bytes"));
+
+        unsafe { ptr.as_ptr().write(value) };
+
+        Self {
+            ptr,
+            _marker: PhantomData,
+        }
+    }
+
+    /// # Safety
+    ///
+    /// `ptr` must point to a valid `T` allocated using FMalloc, and must not be freed by anyone else
+    pub unsafe fn from_raw(ptr: *mut T) -> Self {
+        Self {
+            ptr: NonNull::new_unchecked(ptr),
+            _marker: PhantomData,
+        }
+    }
+
+    /// Gives up ownership of the memory, e.g. to hand the value over to the engine
+    pub fn into_raw(self) -> *mut T {
+        ManuallyDrop::new(self).ptr.as_ptr()
+    }
+
+    pub fn as_ptr(&self) -> *mut T {
+        self.ptr.as_ptr()
+    }
+
+    /// Moves the value out and frees the memory
+    pub fn into_inner(self) -> T {
+        let ptr = self.into_raw();
+
+        unsafe {
+            let value = ptr.read();
+            FMalloc::get().free(ptr as _);
+
+            value
+        }
+    }
+}
+
+impl<T: Default> Default for UeBox<T> {
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for UeBox<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
+
+impl<T> Deref for UeBox<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        unsafe { self.ptr.as_ref() }
+    }
+}
+
+impl<T> DerefMut for UeBox<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        unsafe { self.ptr.as_mut() }
+    }
+}
+
+impl<T> Drop for UeBox<T> {
+    fn drop(&mut self) {
+        unsafe {
+            std::ptr::drop_in_place(self.ptr.as_ptr());
+            FMalloc::get().free(self.ptr.as_ptr() as _);
+        }
+    }
+}
+
+unsafe impl<T: Send> Send for UeBox<T> {}
+unsafe impl<T: Sync> Sync for UeBox<T> {}
+
+/// Allocator backed by FMalloc
+///
+/// Implements [`GlobalAlloc`], and `Allocator` with the `allocator_api` feature (nightly only), so that e.g.
+/// `Vec<T, UeAllocator>` can be used for memory the engine frees later on.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct UeAllocator;
+
+impl UeAllocator {
+    fn size_and_align(layout: Layout) -> Option<(u32, u32)> {
+        let size = u32::try_from(layout.size().max(1)).ok()?;
+        let align = u32::try_from(layout.align()).ok()?;
+
+        Some((size, align))
+    }
+}
+
+unsafe impl GlobalAlloc for UeAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        match Self::size_and_align(layout) {
+            Some((size, align)) => FMalloc::get().malloc(size, align) as _,
+            None => null_mut(),
+        }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, _layout: Layout) {
+        FMalloc::get().free(ptr as _)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let Ok(layout) = Layout::from_size_align(new_size, layout.align()) else {
+            return null_mut();
+        };
+
+        match Self::size_and_align(layout) {
+            Some((size, align)) => FMalloc::get().realloc(ptr as _, size, align) as _,
+            None => null_mut(),
+        }
+    }
+}
+
+#[cfg(feature = "allocator_api")]
+unsafe impl std::alloc::Allocator for UeAllocator {
+    fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, std::alloc::AllocError> {
+        let data = unsafe { GlobalAlloc::alloc(self, layout) };
+        let data = NonNull::new(data).ok_or(std::alloc::AllocError)?;
+
+        Ok(NonNull::slice_from_raw_parts(data, layout.size()))
+    }
+
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+        GlobalAlloc::dealloc(self, ptr.as_ptr(), layout)
+    }
+}
+
+/// Growable buffer whose memory is allocated using FMalloc
+///
+/// Unlike [`TArrayOwned`] this doesn't mirror an engine layout, it's meant for buffers that are passed
+/// as a pointer and length to functions that fill them.
+pub struct UeVec<T> {
+    data: *mut T,
+    len: usize,
+    capacity: usize,
+}
+
+impl<T> UeVec<T> {
+    pub const fn new() -> Self {
+        Self {
+            data: null_mut(),
+            len: 0,
+            capacity: 0,
+        }
+    }
+
+    pub fn with_capacity(capacity: usize) -> Self {
+        let mut result = Self::new();
+        result.grow_to(capacity);
+
+        result
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn as_ptr(&self) -> *const T {
+        self.data
+    }
+
+    pub fn as_mut_ptr(&mut self) -> *mut T {
+        self.data
+    }
+
+    /// # Safety
+    ///
+    /// `len` must not exceed the capacity, and the first `len` elements must be initialized
+    pub unsafe fn set_len(&mut self, len: usize) {
+        debug_assert!(len <= self.capacity);
+
+        self.len = len;
+    }
+
+    /// Makes sure there's room for at least `additional` more elements
+    pub fn reserve(&mut self, additional: usize) {
+        let required = self
+            .len
+            .checked_add(additional)
+            .expect("UeVec capacity overflow");
+
+        if required <= self.capacity {
+            return;
+        }
+
+        self.grow_to(required.max(self.capacity * 2).max(4));
+    }
+
+    pub fn push(&mut self, value: T) {
+        self.reserve(1);
+
+        unsafe { self.data.add(self.len).write(value) };
+        self.len += 1;
+    }
+
+    pub fn pop(&mut self) -> Option<T> {
+        if self.len == 0 {
+            return None;
+        }
+
+        self.len -= 1;
+        unsafe { Some(self.data.add(self.len).read()) }
+    }
+
+    /// Drops the elements past `len`
+    pub fn truncate(&mut self, len: usize) {
+        if len >= self.len {
+            return;
+        }
+
+        let elements =
+            std::ptr::slice_from_raw_parts_mut(unsafe { self.data.add(len) }, self.len - len);
+        self.len = len;
+
+        unsafe { std::ptr::drop_in_place(elements) };
+    }
+
+    pub fn clear(&mut self) {
+        self.truncate(0);
+    }
+
+    fn grow_to(&mut self, capacity: usize) {
+        if capacity <= self.capacity {
+            return;
+        }
+
+        // Zero sized types never need memory, but still need a non-null pointer
+        if size_of::<T>() == 0 {
+            self.data = NonNull::dangling().as_ptr();
+            self.capacity = usize::MAX;
+            return;
+        }
+
+        let size = capacity
+            .checked_mul(size_of::<T>())
+            .and_then(|size| u32::try_from(size).ok())
+            .expect("UeVec capacity overflow");
+
+        let data =
+            unsafe { FMalloc::get().realloc(self.data as _, size, std::mem::align_of::<T>() as _) };
+
+        assert!(!data.is_null(), "FMalloc failed to allocate {size} bytes");
+
+        self.data = data as _;
+        self.capacity = capacity;
+    }
+
+    /// Moves the elements into a Vec and frees the original memory
+    pub fn into_vec(self) -> Vec<T> {
+        let mut this = ManuallyDrop::new(self);
+        let mut result = Vec::with_capacity(this.len);
+
+        if this.data.is_null() {
+            return result;
+        }
+
+        unsafe {
+            std::ptr::copy_nonoverlapping(this.data, result.as_mut_ptr(), this.len);
+            result.set_len(this.len);
+
+            this.len = 0;
+            this.free();
+        }
+
+        result
+    }
+
+    unsafe fn free(&mut self) {
+        if !self.data.is_null() && size_of::<T>() != 0 {
+            FMalloc::get().free(self.data as _);
+        }
+    }
+}
+
+impl<T> Default for UeVec<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for UeVec<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T> Extend<T> for UeVec<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        let iter = iter.into_iter();
+        self.reserve(iter.size_hint().0);
+
+        for value in iter {
+            self.push(value);
+        }
+    }
+}
+
+impl<T> FromIterator<T> for UeVec<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut result = Self::new();
+        result.extend(iter);
+
+        result
+    }
+}
+
+impl<T> Deref for UeVec<T> {
+    type Target = [T];
+
+    fn deref(&self) -> &Self::Target {
+        if self.data.is_null() {
+            return &[];
+        }
+
+        unsafe { std::slice::from_raw_parts(self.data, self.len) }
+    }
+}
+
+impl<T> DerefMut for UeVec<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        if self.data.is_null() {
+            return &mut [];
+        }
+
+        unsafe { std::slice::from_raw_parts_mut(self.data, self.len) }
+    }
+}
+
+impl<T> IntoIterator for UeVec<T> {
+    type Item = T;
+    type IntoIter = std::vec::IntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.into_vec().into_iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a UeVec<T> {
+    type Item = &'a T;
+    type IntoIter = std::slice::Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a mut UeVec<T> {
+    type Item = &'a mut T;
+    type IntoIter = std::slice::IterMut<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+impl<T> Drop for UeVec<T> {
+    fn drop(&mut self) {
+        self.clear();
+
+        unsafe { self.free() };
+    }
+}
+
+/// TArray whose memory is owned by us, and freed using FMalloc when dropped
+#[repr(transparent)]
+pub struct TArrayOwned<T> {
+    array: TArray<T>,
+}
+
+impl<T> TArrayOwned<T> {
+    pub fn new() -> Self {
+        Self {
+            array: TArray {
+                data: null_mut(),
+                count: 0,
+                capacity: 0,
+            },
+        }
+    }
+
+    pub fn with_capacity(capacity: usize) -> Self {
+        let mut result = Self::new();
+        result.grow_to(capacity);
+
+        result
+    }
+
+    /// Copies the elements of `vec` into memory allocated using FMalloc
+    pub fn from_vec(vec: Vec<T>) -> Self {
+        let mut result = Self::with_capacity(vec.len());
+        if vec.is_empty() {
+            return result;
+        }
+
+        let mut vec = ManuallyDrop::new(vec);
+
+        unsafe {
+            std::ptr::copy_nonoverlapping(vec.as_ptr(), result.data, vec.len());
+            result.count = vec.len() as _;
+
+            // The elements have been moved, so only the allocation of the Vec is freed
+            vec.set_len(0);
+            ManuallyDrop::drop(&mut vec);
+        }
+
+        result
+    }
+
+    /// # Safety
+    ///
+    /// The memory of `array` must have been allocated using FMalloc, and must not be freed by anyone else
+    pub unsafe fn from_raw(array: TArray<T>) -> Self {
+        Self { array }
+    }
+
+    /// Gives up ownership of the memory, e.g. to hand the array over to the engine
+    pub fn into_raw(self) -> TArray<T> {
+        let this = ManuallyDrop::new(self);
+
+        TArray {
+            data: this.data,
+            count: this.count,
+            capacity: this.capacity,
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity.max(0) as _
+    }
+
+    /// Makes sure there's room for at least `additional` more elements, growing like the engine does
+    pub fn reserve(&mut self, additional: usize) {
+        let required = self
+            .len()
+            .checked_add(additional)
+            .expect("TArray capacity overflow");
+
+        if required <= self.capacity() {
+            return;
+        }
+
+        // Same slack as FMemory's DefaultCalculateSlackGrow, without the bucket rounding
+        let grown = required
+            .saturating_add(3 * required / 8)
+            .saturating_add(16)
+            .min(i32::MAX as usize);
+
+        self.grow_to(grown.max(required));
+    }
+
+    pub fn push(&mut self, value: T) {
+        self.reserve(1);
+
+        unsafe { self.data.add(self.len()).write(value) };
+        self.count += 1;
+    }
+
+    /// Drops all elements, keeping the allocated memory
+    pub fn clear(&mut self) {
+        let elements = self.as_mut_slice() as *mut [T];
+        self.count = 0;
+
+        unsafe { std::ptr::drop_in_place(elements) };
+    }
+
+    fn grow_to(&mut self, capacity: usize) {
+        if capacity <= self.capacity() {
+            return;
+        }
+
+        let capacity = i32::try_from(capacity).expect("TArray capacity overflow");
+        let size = (capacity as usize)
+            .checked_mul(size_of::<T>())
+            .and_then(|size| u32::try_from(size).ok())
+            .expect("TArray capacity overflow");
+
+        let data =
+            unsafe { FMalloc::get().realloc(self.data as _, size, std::mem::align_of::<T>() as _) };
+
+        assert!(!data.is_null(), "FMalloc failed to allocate {size} bytes");
+
+        self.data = data as _;
+        self.capacity = capacity;
+    }
+
+    /// Moves the elements into a Vec and frees the original memory
+    pub fn into_vec(self) -> Vec<T> {
+        let this = ManuallyDrop::new(self);
+        let mut result = Vec::with_capacity(this.len());
+
+        if !this.data.is_null() {
+            unsafe {
+                std::ptr::copy_nonoverlapping(this.data, result.as_mut_ptr(), this.len());
+                result.set_len(this.len());
+
+                FMalloc::get().free(this.data as _);
+            }
+        }
+
+        result
+    }
+}
+
+impl<T> Default for TArrayOwned<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> From<Vec<T>> for TArrayOwned<T> {
+    fn from(value: Vec<T>) -> Self {
+        Self::from_vec(value)
+    }
+}
+
+impl<T> Deref for TArrayOwned<T> {
+    type Target = TArray<T>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.array
+    }
+}
+
+impl<T> DerefMut for TArrayOwned<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.array
+    }
+}
+
+impl<T> IntoIterator for TArrayOwned<T> {
+    type Item = T;
+    type IntoIter = std::vec::IntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.into_vec().into_iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a TArrayOwned<T> {
+    type Item = &'a T;
+    type IntoIter = std::slice::Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a mut TArrayOwned<T> {
+    type Item = &'a mut T;
+    type IntoIter = std::slice::IterMut<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+impl<T> Drop for TArrayOwned<T> {
+    fn drop(&mut self) {
+        if self.data.is_null() {
+            return;
+        }
+
+        unsafe {
+            std::ptr::drop_in_place(self.as_mut_slice());
+            FMalloc::get().free(self.data as _);
+        }
+    }
+}
+
+/// Memory layout of an engine TBitArray (with the default inline allocator)
+#[repr(C)]
+struct TBitArray {
+    inline_data: [u32; 4],
+    secondary_data: *mut u32,
+    num_bits: i32,
+    max_bits: i32,
+}
+
+impl TBitArray {
+    fn get(&self, index: usize) -> bool {
+        if index >= self.num_bits.min(self.max_bits).max(0) as usize {
+            return false;
+        }
+
+        if self.secondary_data.is_null() {
+            // Never read past the inline words, even if the bit count claims there are more
+            return self
+                .inline_data
+                .get(index / 32)
+                .is_some_and(|word| word & (1 << (index % 32)) != 0);
+        }
+
+        unsafe { *self.secondary_data.add(index / 32) & (1 << (index % 32)) != 0 }
+    }
+}
+
+/// Memory layout of an engine TSparseArray
+///
+/// Elements are stored in a regular TArray, and a bit array marks which slots hold an element.
+/// Free slots contain a free list link instead of an element.
+#[repr(C)]
+struct TSparseArray<T> {
+    data: TArray<T>,
+    allocation_flags: TBitArray,
+    first_free_index: i32,
+    num_free_indices: i32,
+}
+
+impl<T> TSparseArray<T> {
+    fn len(&self) -> usize {
+        self.data
+            .len()
+            .saturating_sub(self.num_free_indices.max(0) as _)
+    }
+
+    /// Iterates over the allocated slots
+    ///
+    /// Slots past the end of the element buffer are never read, even if the bit array claims they are allocated.
+    fn iter(&self) -> impl Iterator<Item = &T> {
+        self.data
+            .iter()
+            .enumerate()
+            .filter(|(index, _)| self.allocation_flags.get(*index))
+            .map(|(_, element)| element)
+    }
+}
+
+/// Memory layout of an element in a TSet
+#[repr(C)]
+struct TSetElement<T> {
+    value: T,
+    hash_next_id: i32,
+    hash_index: i32,
+}
+
+/// Memory layout of an engine TSet
+///
+/// Consists of a TSparseArray of elements, followed by the hash buckets
+/// (inline allocated, with a single inline bucket). This layout is the same in UE4.27 and UE5.
+///
+/// Only supports reading, and should only be accessed by reference to memory owned by the engine.
+#[repr(C)]
+pub struct TSet<T> {
+    elements: TSparseArray<TSetElement<T>>,
+    hash_inline: i32,
+    hash_secondary: *mut i32,
+    hash_size: i32,
+}
+
+const _: () = assert!(size_of::<TSet<u64>>() == 0x50);
+
+impl<T> TSet<T> {
+    pub fn len(&self) -> usize {
+        self.elements.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Iterates over the occupied slots of the set
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.elements.iter().map(|element| &element.value)
+    }
+
+    /// Checks whether `value` is in the set, by comparing it to every element
+    pub fn contains(&self, value: &T) -> bool
+    where
+        T: PartialEq,
+    {
+        self.iter().any(|element| element == value)
+    }
+}
+
+/// Memory layout of an engine TPair, the element type of a TMap
+#[repr(C)]
+pub struct TPair<K, V> {
+    pub key: K,
+    pub value: V,
+}
+
+/// Memory layout of an engine TMap, a TSet of key/value pairs
+///
+/// Only supports reading, and should only be accessed by reference to memory owned by the engine.
+///
+/// The layout (80 bytes on 64-bit, for both UE4.27 and UE5) is:
+///
+/// | Offset | Field                                                            |
+/// |--------|------------------------------------------------------------------|
+/// | 0x00   | `TArray<TSetElement<TPair<K, V>>>` elements                      |
+/// | 0x10   | `TBitArray` allocation flags (4 inline words, pointer, num, max) |
+/// | 0x30   | first free index, number of free indices                         |
+/// | 0x38   | hash buckets (inline bucket, pointer)                            |
+/// | 0x48   | hash size                                                        |
+#[repr(C)]
+pub struct TMap<K, V> {
+    pairs: TSet<TPair<K, V>>,
+}
+
+const _: () = assert!(size_of::<TMap<u64, u64>>() == 0x50);
+
+impl<K, V> TMap<K, V> {
+    pub fn len(&self) -> usize {
+        self.pairs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&K, &V)> {
+        self.pairs.iter().map(|pair| (&pair.key, &pair.value))
+    }
+
+    /// Looks up the value for `key`, by comparing it to every key in the map
+    pub fn get(&self, key: &K) -> Option<&V>
+    where
+        K: PartialEq,
+    {
+        self.iter().find(|(k, _)| *k == key).map(|(_, v)| v)
+    }
+}
+
+/// Engine string, a null-terminated UTF-16 TArray
+///
+/// Has the same layout as the engine type, so it can be used directly in UFunction parameter structs.
+/// Strings owned by the engine should only be accessed by reference, since the memory is freed on drop.
+#[repr(transparent)]
+pub struct FString {
+    data: TArrayOwned<u16>,
+}
+
+impl FString {
+    pub fn new() -> Self {
+        Self {
+            data: TArrayOwned::new(),
+        }
+    }
+
+    /// The characters of the string, without the null terminator
+    pub fn as_wide(&self) -> &[u16] {
+        match self.data.as_slice() {
+            [chars @ .., 0] => chars,
+            chars => chars,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.as_wide().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.as_wide().is_empty()
+    }
+}
+
+impl Default for FString {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl From<&str> for FString {
+    fn from(value: &str) -> Self {
+        Self {
+            data: TArrayOwned::from_vec(encode_wstr(value)),
+        }
+    }
+}
+
+impl fmt::Display for FString {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&String::from_utf16_lossy(self.as_wide()))
+    }
+}
+
+impl fmt::Debug for FString {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self.to_string())
+    }
+}
+
+/// Memory layout of an engine FText, a shared reference to the text data and some flags
+///
+/// The text data itself differs between engine versions, so it can only be read using [`FText::try_to_string`].
+/// The layout matches UE4, UE5 has no separate reference controller, so only use texts by reference.
+#[repr(C)]
+pub struct FText {
+    data: *mut c_void,
+    reference_controller: *mut c_void,
+    flags: u32,
+}
+
+const _: () = assert!(size_of::<FText>() == 0x18);
+
+impl FText {
+    /// Converts the text to a string, returning `None` if it can't be read
+    ///
+    /// Calls `KismetTextLibrary::Conv_TextToString`, which works on all engine versions. If that
+    /// function can't be found, the display string is read from the text data directly, which only
+    /// matches the layout used by UE 4.20 up to 4.27.
+    pub fn try_to_string(&self) -> Option<String> {
+        if self.data.is_null() {
+            return None;
+        }
+
+        self.to_string_using_library()
+            .or_else(|| unsafe { self.read_display_string() })
+    }
+
+    fn to_string_using_library(&self) -> Option<String> {
+        static FUNCTION: AtomicPtr<c_void> = AtomicPtr::new(null_mut());
+        static LIBRARY: AtomicPtr<c_void> = AtomicPtr::new(null_mut());
+
+        let api = API::get();
+        let function = find_object_cached(&FUNCTION, || {
+            api.find_uobject::<UFunction>(
+                "Function /Script/Engine.KismetTextLibrary.Conv_TextToString",
+            )
+        })?;
+        let library = find_object_cached(&LIBRARY, || {
+            api.find_uobject::<UClass>("Class /Script/Engine.KismetTextLibrary")
+                .map(|class| class.get_class_default_object())
+        })?;
+
+        let size = usize::try_from(function.get_properties_size()).ok()?;
+        let offset = |name| {
+            let property = FProperty::from_ptr_safe(function.find_property(name).to_ptr())?;
+
+            usize::try_from(property.get_offset()).ok()
+        };
+        let in_text = offset("InText")?;
+        let return_value = offset("ReturnValue")?;
+
+        // UE5 only stores the reference and the flags, so the text can be smaller than our layout
+        let text_size = match return_value.checked_sub(in_text) {
+            Some(distance) if distance > 0 => distance.min(size_of::<FText>()),
+            _ => size_of::<FText>().min(size.checked_sub(in_text)?),
+        };
+
+        if return_value + size_of::<FString>() > size {
+            return None;
+        }
+
+        let mut params = vec![0u64; size.div_ceil(size_of::<u64>())];
+
+        // The text is passed by reference, so copying it without touching the reference count is fine
+        // as long as the copy is never destroyed
+        unsafe {
+            let params = params.as_mut_ptr() as *mut u8;
+
+            params
+                .add(in_text)
+                .copy_from_nonoverlapping(self as *const FText as *const u8, text_size);
+            library.process_event(function, params as _);
+
+            let string = (params.add(return_value) as *const FString).read_unaligned();
+
+            Some(string.to_string())
+        }
+    }
+
+    /// # Safety
+    ///
+    /// The text data must be valid, the layout of what it points to is validated as far as possible
+    unsafe fn read_display_string(&self) -> Option<String> {
+        // ITextData vtable, followed by the shared pointer to the display string
+        let string = *(self.data as *const *const TArray<u16>).add(1);
+        if string.is_null() || !string.is_aligned() {
+            return None;
+        }
+
+        let string = &*string;
+        let len = string.len();
+
+        if len == 0 {
+            return Some("".to_string());
+        }
+
+        if string.begin().is_null() || len > string.capacity.max(0) as usize || len > 0x10000 {
+            return None;
+        }
+
+        match string.as_slice() {
+            [chars @ .., 0] => Some(String::from_utf16_lossy(chars)),
+            _ => None,
+        }
+    }
+}
+
+/// Color with floating point components, laid out like the engine type
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct FLinearColor {
+    pub r: f32,
+    pub g: f32,
+    pub b: f32,
+    pub a: f32,
+}
+
+impl FLinearColor {
+    pub const WHITE: FLinearColor = FLinearColor::new(1.0, 1.0, 1.0, 1.0);
+    pub const BLACK: FLinearColor = FLinearColor::new(0.0, 0.0, 0.0, 1.0);
+    pub const RED: FLinearColor = FLinearColor::new(1.0, 0.0, 0.0, 1.0);
+    pub const GREEN: FLinearColor = FLinearColor::new(0.0, 1.0, 0.0, 1.0);
+    pub const BLUE: FLinearColor = FLinearColor::new(0.0, 0.0, 1.0, 1.0);
+
+    pub const fn new(r: f32, g: f32, b: f32, a: f32) -> Self {
+        Self { r, g, b, a }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::{rc::Rc, sync::atomic::AtomicUsize};
+
+    /// Counts how many times it was dropped using the shared counter
+    struct DropCounter(Rc<Cell<usize>>);
+
+    impl Drop for DropCounter {
+        fn drop(&mut self) {
+            self.0.set(self.0.get() + 1);
+        }
+    }
+
+    #[test]
+    fn ue_vec_growth_does_not_leak() {
+        mock::install();
+
+        let mut vec = UeVec::new();
+        for i in 0..1000u64 {
+            vec.push(i);
+        }
+
+        assert_eq!(mock::live_allocations(), 1);
+        assert_eq!(vec.len(), 1000);
+        assert!(vec.iter().copied().eq(0..1000));
+
+        drop(vec);
+        assert_eq!(mock::live_allocations(), 0);
+    }
+
+    #[test]
+    fn ue_vec_drops_elements_once() {
+        mock::install();
+
+        let drops = Rc::new(Cell::new(0));
+        let mut vec = (0..10)
+            .map(|_| DropCounter(drops.clone()))
+            .collect::<UeVec<_>>();
+
+        vec.truncate(6);
+        assert_eq!(drops.get(), 4);
+
+        drop(vec.pop());
+        assert_eq!(drops.get(), 5);
+
+        drop(vec);
+        assert_eq!(drops.get(), 10);
+        assert_eq!(mock::live_allocations(), 0);
+    }
+
+    #[test]
+    fn ue_vec_into_vec_moves_elements() {
+        mock::install();
+
+        let drops = Rc::new(Cell::new(0));
+        let vec = (0..3)
+            .map(|_| DropCounter(drops.clone()))
+            .collect::<UeVec<_>>()
+            .into_vec();
+
+        assert_eq!(mock::live_allocations(), 0);
+        assert_eq!(drops.get(), 0);
+
+        drop(vec);
+        assert_eq!(drops.get(), 3);
+    }
+
+    #[test]
+    fn t_array_owned_grows_without_leaking() {
+        mock::install();
+
+        let mut array = TArrayOwned::new();
+        for i in 0..100u64 {
+            array.push(i);
+        }
+
+        assert_eq!(array.len(), 100);
+        assert!(array.capacity() >= 100);
+        assert!(array.iter().copied().eq(0..100));
+        assert_eq!(mock::live_allocations(), 1);
+
+        drop(array);
+        assert_eq!(mock::live_allocations(), 0);
+    }
+
+    #[test]
+    fn t_array_owned_clear_drops_elements() {
+        mock::install();
+
+        let drops = Rc::new(Cell::new(0));
+        let mut array = TArrayOwned::from_vec((0..5).map(|_| DropCounter(drops.clone())).collect());
+        let capacity = array.capacity();
+
+        array.clear();
+        assert_eq!(drops.get(), 5);
+        assert!(array.is_empty());
+        assert_eq!(array.capacity(), capacity);
+
+        drop(array);
+        assert_eq!(drops.get(), 5);
+        assert_eq!(mock::live_allocations(), 0);
+    }
+
+    #[test]
+    fn t_array_owned_vec_round_trip() {
+        mock::install();
+
+        let array = TArrayOwned::from_vec(vec![1u32, 2, 3]);
+        assert_eq!(array.as_slice(), [1, 2, 3]);
+
+        assert_eq!(array.into_vec(), [1, 2, 3]);
+        assert_eq!(mock::live_allocations(), 0);
+    }
+
+    #[test]
+    fn empty_t_array_owned() {
+        mock::install();
+
+        let mut array = TArrayOwned::<u32>::new();
+        array.clear();
+
+        assert!(TArrayOwned::<u32>::from_vec(vec![]).into_vec().is_empty());
+        assert!(array.into_vec().is_empty());
+        assert_eq!(mock::live_allocations(), 0);
+    }
+
+    #[test]
+    fn empty_ue_vec_into_vec() {
+        mock::install();
+
+        assert!(UeVec::<u32>::new().into_vec().is_empty());
+        assert_eq!(UeVec::<u32>::new().into_iter().count(), 0);
+        assert_eq!(mock::live_allocations(), 0);
+    }
+
+    #[test]
+    fn motion_controller_state_round_trip() {
+        mock::install();
+
+        // Never dereferenced, only used as the key of the tracked values
+        let mut storage = 0u64;
+        let state = MotionControllerState::from_ptr(&mut storage as *mut u64 as _);
+
+        assert!(state.tracked_values().is_none());
+        assert_eq!(state.get_hand(), 1);
+        assert!(!state.is_permanent());
+
+        state
+            .builder()
+            .hand(0)
+            .permanent(true)
+            .location_offset(UEVR_Vector3f {
+                x: 1.0,
+                y: 2.0,
+                z: 3.0,
+            })
+            .apply();
+
+        assert_eq!(state.get_hand(), 0);
+        assert!(state.is_permanent());
+        assert_eq!(state.get_location_offset().z, 3.0);
+        assert_eq!(state.get_rotation_offset().w, 1.0);
+        assert!(format!("{state:?}").contains("hand: 0"));
+
+        state.forget_values();
+        assert!(state.tracked_values().is_none());
+        assert!(format!("{state:?}").contains("set_by_plugin: None"));
+    }
+
+    #[test]
+    fn load_function_table_races() {
+        static TABLE: u64 = 0;
+        static CACHE: AtomicPtr<u64> = AtomicPtr::new(null_mut());
+        static INIT_CALLS: AtomicUsize = AtomicUsize::new(0);
+
+        let init = || {
+            INIT_CALLS.fetch_add(1, Ordering::Relaxed);
+            &TABLE as *const u64
+        };
+
+        let threads = (0..8)
+            .map(|_| std::thread::spawn(move || load_function_table(&CACHE, init) as usize))
+            .collect::<Vec<_>>();
+
+        for thread in threads {
+            assert_eq!(thread.join().unwrap(), &TABLE as *const u64 as usize);
+        }
+
+        // Once stored, the table is never looked up again
+        let calls = INIT_CALLS.load(Ordering::Relaxed);
+        assert!((1..=8).contains(&calls));
+
+        load_function_table(&CACHE, init);
+        assert_eq!(INIT_CALLS.load(Ordering::Relaxed), calls);
+    }
+
+    define_object!(
+        UMockActor,
+        "MockActor",
+        @class("Class /Script/Engine.MockActor"),
+        @impls(RUObject)
+    );
+
+    define_object!(UMockComponent, "MockComponent", @impls(RUObject));
+
+    /// Held by the tests that count class lookups, as invalidating the caches affects all of them
+    static CLASS_CACHE_LOCK: Mutex<()> = Mutex::new(());
+
+    #[test]
+    fn static_class_is_looked_up_once() {
+        mock::install();
+        let _lock = CLASS_CACHE_LOCK.lock().unwrap();
+
+        const PATH: &str = "Class /Script/Engine.MockActor";
+        let mut class = 0u64;
+        mock::add_object(PATH, &mut class as *mut u64 as _);
+
+        for _ in 0..3 {
+            assert_eq!(
+                UMockActor::static_class().to_ptr(),
+                &mut class as *mut u64 as _
+            );
+        }
+        assert_eq!(mock::find_uobject_calls(PATH), 1);
+
+        invalidate_static_class_cache();
+        UMockActor::static_class();
+        assert_eq!(mock::find_uobject_calls(PATH), 2);
+    }
+
+    #[test]
+    fn guessed_static_class_is_looked_up_once() {
+        mock::install();
+        let _lock = CLASS_CACHE_LOCK.lock().unwrap();
+
+        let mut class = 0u64;
+        mock::add_object(
+            "Class /Script/Engine.MockComponent",
+            &mut class as *mut u64 as _,
+        );
+
+        UMockComponent::static_class();
+        UMockComponent::static_class();
+
+        assert_eq!(
+            mock::find_uobject_calls("Class /Script/CoreUObject.MockComponent"),
+            1
+        );
+        assert_eq!(
+            mock::find_uobject_calls("Class /Script/Engine.MockComponent"),
+            1
+        );
+    }
+
+    #[test]
+    fn objects_destroyed_while_listing_are_skipped() {
+        mock::install();
+
+        let mut objects = [0u64; 3];
+        let [a, b, _] = objects
+            .each_mut()
+            .map(|object| object as *mut u64 as *mut c_void);
+        mock::set_class_objects(3, &[a, b]);
+
+        let class = UClass::from_ptr(a);
+        let found = class.get_objects_matching_raw(false);
+
+        assert_eq!(
+            found
+                .iter()
+                .map(|object| object.to_ptr())
+                .collect::<Vec<_>>(),
+            [a, b]
+        );
+        assert_eq!(mock::live_allocations(), 0);
+    }
+
+    #[test]
+    fn t_array_ownership() {
+        mock::install();
+
+        let drops = Rc::new(Cell::new(0));
+        let array = TArrayOwned::from_vec((0..3).map(|_| DropCounter(drops.clone())).collect());
+        assert_eq!(mock::live_allocations(), 1);
+
+        // A borrowed view neither drops the elements nor frees the engine's memory
+        let view = unsafe { TArrayRef::from_raw(&*array as *const TArray<DropCounter>) };
+        assert_eq!(view.into_iter().count(), 3);
+        assert_eq!(drops.get(), 0);
+        assert_eq!(mock::live_allocations(), 1);
+
+        drop(array);
+        assert_eq!(drops.get(), 3);
+        assert_eq!(mock::live_allocations(), 0);
+    }
+
+    #[test]
+    fn t_array_end_is_past_the_last_element() {
+        let mut elements = [[1u64; 3], [2; 3], [3; 3]];
+        let array = TArray {
+            data: elements.as_mut_ptr(),
+            count: 3,
+            capacity: 3,
+        };
+
+        assert_eq!(size_of::<[u64; 3]>(), 24);
+        assert_eq!(array.end() as usize - array.begin() as usize, 3 * 24);
+        assert_eq!(array.get(2), Some(&[3; 3]));
+        assert_eq!(array.get(3), None);
+    }
+
+    /// Lays out an engine TSet or TMap by hand, with the elements at `data` and the allocation flags in
+    /// `inline_bits` or `secondary_bits`
+    fn sparse_blob(
+        data: *mut c_void,
+        count: i32,
+        inline_bits: u32,
+        secondary_bits: *mut u32,
+        num_bits: i32,
+        num_free: i32,
+    ) -> [u64; 10] {
+        let mut blob = [0u64; 10];
+        let base = blob.as_mut_ptr() as *mut u8;
+
+        unsafe {
+            (base as *mut *mut c_void).write(data);
+            (base.add(0x08) as *mut i32).write(count);
+            (base.add(0x0C) as *mut i32).write(count);
+            (base.add(0x10) as *mut u32).write(inline_bits);
+            (base.add(0x20) as *mut *mut u32).write(secondary_bits);
+            (base.add(0x28) as *mut i32).write(num_bits);
+            (base.add(0x2C) as *mut i32).write(num_bits);
+            (base.add(0x30) as *mut i32).write(if num_free > 0 { 1 } else { -1 });
+            (base.add(0x34) as *mut i32).write(num_free);
+        }
+
+        blob
+    }
+
+    #[test]
+    fn t_map_from_engine_layout() {
+        // Slot 1 is free, the others hold 0 => 10, 2 => 30 and 3 => 40
+        let mut elements = [0, 1, 2, 3].map(|key| TSetElement {
+            value: TPair {
+                key,
+                value: (key as u64 + 1) * 10,
+            },
+            hash_next_id: -1,
+            hash_index: 0,
+        });
+
+        let blob = sparse_blob(elements.as_mut_ptr() as _, 4, 0b1101, null_mut(), 4, 1);
+        let map = unsafe { &*(blob.as_ptr() as *const TMap<u32, u64>) };
+
+        assert_eq!(map.len(), 3);
+        assert_eq!(
+            map.iter().map(|(k, v)| (*k, *v)).collect::<Vec<_>>(),
+            [(0, 10), (2, 30), (3, 40)]
+        );
+        assert_eq!(map.get(&2), Some(&30));
+        assert_eq!(map.get(&1), None);
+    }
+
+    #[test]
+    fn t_set_with_secondary_allocation_flags() {
+        let mut elements = (0..40u32)
+            .map(|value| TSetElement {
+                value,
+                hash_next_id: -1,
+                hash_index: 0,
+            })
+            .collect::<Vec<_>>();
+
+        // Every even slot and slot 33 are allocated. The flags claim 64 bits, more than there are slots.
+        let mut bits = [0x5555_5555u32, 0b10];
+        let blob = sparse_blob(elements.as_mut_ptr() as _, 40, 0, bits.as_mut_ptr(), 64, 23);
+        let set = unsafe { &*(blob.as_ptr() as *const TSet<u32>) };
+
+        let expected = (0..32).step_by(2).chain([33]).collect::<Vec<_>>();
+        assert_eq!(set.iter().copied().collect::<Vec<_>>(), expected);
+        assert_eq!(set.len(), 17);
+        assert!(set.contains(&33) && !set.contains(&34));
+    }
+
+    #[test]
+    fn t_bit_array_never_reads_past_inline_words() {
+        let blob = sparse_blob(null_mut(), 0, u32::MAX, null_mut(), 1024, 0);
+        let set = unsafe { &*(blob.as_ptr() as *const TSet<u32>) };
+
+        assert!(set.elements.allocation_flags.get(31));
+        assert!(!set.elements.allocation_flags.get(200));
+        assert!(set.is_empty());
+    }
+
+    #[test]
+    fn console_object_names_are_decoded() {
+        mock::install();
+
+        let mut object = 0u64;
+        let object = &mut object as *mut u64 as *mut IConsoleObject;
+        let names = ["r.ScreenPercentage", "vr.Größe"].map(encode_wstr);
+
+        let element = |key: *const u16, value| ConsoleObjectElement {
+            key: key as _,
+            unk: [0; 2],
+            value,
+            unk2: [0; 2],
+        };
+
+        // Entries without a name or an object are skipped
+        let mut elements = [
+            element(names[0].as_ptr(), object),
+            element(null(), object),
+            element(names[1].as_ptr(), null_mut()),
+            element(names[1].as_ptr(), object),
+        ];
+        let mut array = TArray {
+            data: elements.as_mut_ptr(),
+            count: 4,
+            capacity: 4,
+        };
+
+        let manager = FConsoleManager::from_ptr(&mut array as *mut TArray<_> as _);
+        let objects = manager.get_console_objects();
+
+        assert_eq!(
+            objects
+                .iter()
+                .map(|(name, _)| name.as_str())
+                .collect::<Vec<_>>(),
+            ["r.ScreenPercentage", "vr.Größe"]
+        );
+    }
+
+    /// Fills `items` with objects, returning the objects in order
+    fn fill_items(items: &mut [FUObjectItem], storage: &mut [u64]) -> Vec<*mut c_void> {
+        items
+            .iter_mut()
+            .zip(storage)
+            .map(|(item, object)| {
+                item.object = object as *mut u64 as _;
+                item.object as _
+            })
+            .collect()
+    }
+
+    fn empty_item() -> FUObjectItem {
+        FUObjectItem {
+            object: null_mut(),
+            flags: 0,
+            cluster_index: 0,
+            serial_number: 0,
+        }
+    }
+
+    fn walk(array: &FUObjectArray) -> Vec<*mut c_void> {
+        unsafe { array.raw_items() }
+            .map(|(_, item)| item.object as *mut c_void)
+            .collect()
+    }
+
+    #[test]
+    fn raw_items_inlined() {
+        mock::install();
+
+        let mut storage = [0u64; 3];
+        let mut items = [empty_item(); 3];
+        let objects = fill_items(&mut items, &mut storage);
+
+        mock::set_object_array(items.as_mut_ptr() as _, 3, false, true);
+        assert_eq!(
+            walk(&FUObjectArray::from_ptr(NonNull::dangling().as_ptr())),
+            objects
+        );
+    }
+
+    #[test]
+    fn raw_items_chunked() {
+        mock::install();
+
+        // Two chunks, the walk has to move on to the second one after 64K items
+        const PER_CHUNK: usize = RawItemWalker::OBJECTS_PER_CHUNK;
+
+        let mut storage = [0u64; 3];
+        let mut first = vec![empty_item(); PER_CHUNK];
+        let mut second = [empty_item(); 1];
+
+        let mut objects = fill_items(&mut first[PER_CHUNK - 2..], &mut storage[..2]);
+        objects.extend(fill_items(&mut second, &mut storage[2..]));
+
+        let mut chunks = [first.as_ptr(), second.as_ptr()];
+        let mut field = chunks.as_mut_ptr();
+
+        mock::set_object_array(&mut field as *mut _ as _, PER_CHUNK as i32 + 1, true, false);
+
+        let walked = walk(&FUObjectArray::from_ptr(NonNull::dangling().as_ptr()));
+        assert_eq!(walked.len(), PER_CHUNK + 1);
+        assert_eq!(walked[PER_CHUNK - 2..], objects);
+    }
+}
@@ -1,11 +1,41 @@
 use crate::{
-    api::{MotionControllerState, UClass, UObject},
+    api::{
+        load_function_table, FUObjectArray, MotionControllerState, Ptr, RUObject, RUStruct,
+        StaticClass, UClass, UObject,
+    },
     bindings::UEVR_UObjectHookFunctions,
 };
 
-use std::ptr::null;
+use std::{
+    collections::HashMap,
+    ffi::c_void,
+    ptr::null_mut,
+    sync::{atomic::AtomicPtr, LazyLock, Mutex},
+};
+
+/// Callbacks keyed by the pointer of the class they're registered for
+type ClassCallbacks = HashMap<usize, Vec<fn(UObject)>>;
+
+static STATIC_OBJECT_HOOK: AtomicPtr<UEVR_UObjectHookFunctions> = AtomicPtr::new(null_mut());
+
+static OBJECT_CREATE_CALLBACKS: Mutex<Vec<fn(UObject)>> = Mutex::new(Vec::new());
+static OBJECT_DESTROY_CALLBACKS: Mutex<Vec<fn(UObject)>> = Mutex::new(Vec::new());
+static CLASS_CREATE_CALLBACKS: LazyLock<Mutex<ClassCallbacks>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+static CLASS_DESTROY_CALLBACKS: LazyLock<Mutex<ClassCallbacks>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Objects that were alive during the previous engine tick
+static OBJECT_SNAPSHOT: Mutex<Option<ObjectSnapshot>> = Mutex::new(None);
 
-static mut STATIC_OBJECT_HOOK: *const UEVR_UObjectHookFunctions = null();
+struct ObjectSnapshot {
+    /// Classes with destroy callbacks when the objects were matched against them
+    destroy_targets: Vec<usize>,
+    /// Objects mapped to the classes in `destroy_targets` they are an instance of
+    ///
+    /// The class of a destroyed object may already be freed, so it's matched while the object is alive.
+    objects: HashMap<usize, Vec<usize>>,
+}
 
 pub fn activate() {
     let fun = initialize().activate.unwrap();
@@ -35,10 +65,68 @@ pub fn get_objects_by_class(c: UClass, allow_default: bool) -> Vec<UObject> {
     c.get_objects_matching_raw(allow_default)
 }
 
+/// Calls `f` for every object of class `c`, without allocating if there are less than 1024 objects
+pub fn for_each_object_by_class(c: UClass, allow_default: bool, mut f: impl FnMut(UObject)) {
+    const STACK_OBJECTS: usize = 1024;
+
+    activate();
+
+    let fun = initialize().get_objects_by_class.unwrap();
+    let mut buffer = [null_mut(); STACK_OBJECTS];
+
+    let found = unsafe {
+        fun(
+            c.to_handle(),
+            buffer.as_mut_ptr(),
+            STACK_OBJECTS as u32,
+            allow_default,
+        )
+    };
+
+    if found as usize >= STACK_OBJECTS {
+        c.get_objects_matching_raw(allow_default)
+            .into_iter()
+            .for_each(f);
+
+        return;
+    }
+
+    buffer[..found as usize]
+        .iter()
+        .flat_map(|handle| UObject::from_handle_safe(*handle))
+        .for_each(&mut f);
+}
+
 pub fn get_first_object_by_class(c: UClass, allow_default: bool) -> Option<UObject> {
     c.get_first_object_matching_raw(allow_default)
 }
 
+/// Same as [`get_objects_by_class`], but looks up the class of `T` and casts the objects
+///
+/// Returns an empty list if the class of `T` can't be found.
+pub fn get_objects_by_class_typed<T: StaticClass>(allow_default: bool) -> Vec<T> {
+    static_class_or_warn::<T>()
+        .map(|c| c.get_objects_matching(allow_default))
+        .unwrap_or_default()
+}
+
+/// Same as [`get_first_object_by_class`], but looks up the class of `T` and casts the object
+///
+/// Returns `None` if the class of `T` can't be found.
+pub fn get_first_object_by_class_typed<T: StaticClass>(allow_default: bool) -> Option<T> {
+    static_class_or_warn::<T>()?.get_first_object_matching(allow_default)
+}
+
+fn static_class_or_warn<T: StaticClass>() -> Option<UClass> {
+    let class = T::static_class_safe();
+
+    if class.is_none() {
+        crate::warn!("Could not find the class of {}", std::any::type_name::<T>());
+    }
+
+    class
+}
+
 pub fn get_or_add_motion_controller_state(obj: UObject) -> MotionControllerState {
     let fun = initialize().get_or_add_motion_controller_state.unwrap();
 
@@ -53,22 +141,198 @@ pub fn get_motion_controller_state(obj: UObject) -> MotionControllerState {
 
 pub fn remove_motion_controller_state(obj: UObject) {
     let fun = initialize().remove_motion_controller_state.unwrap();
+    get_motion_controller_state(obj).forget_values();
 
     unsafe { fun(obj.to_handle()) }
 }
 
 pub fn remove_all_motion_controller_states() {
     let fun = initialize().remove_all_motion_controller_states.unwrap();
+    MotionControllerState::forget_all_values();
 
     unsafe { fun() }
 }
 
-fn initialize<'a>() -> &'a UEVR_UObjectHookFunctions {
-    unsafe {
-        if STATIC_OBJECT_HOOK.is_null() {
-            STATIC_OBJECT_HOOK = super::API::get().sdk().uobject_hook;
+/// Registers a callback that is called once for every newly created object
+///
+/// UEVR has no native hook for this, so the object array is compared against the previous
+/// engine tick instead. Objects that already exist when the first callback is registered are not reported.
+pub fn on_object_create(callback: fn(UObject)) {
+    OBJECT_CREATE_CALLBACKS.lock().unwrap().push(callback);
+}
+
+/// Registers a callback that is called once for every destroyed object
+///
+/// The object has already been destroyed when the callback is called, so the object
+/// should only be used as an identifier, and must not be accessed.
+pub fn on_object_destroy(callback: fn(UObject)) {
+    OBJECT_DESTROY_CALLBACKS.lock().unwrap().push(callback);
+}
+
+/// Same as [`on_object_create`], but only for instances of `class` (including subclasses)
+pub fn on_class_create(class: UClass, callback: fn(UObject)) {
+    CLASS_CREATE_CALLBACKS
+        .lock()
+        .unwrap()
+        .entry(class.to_ptr() as usize)
+        .or_default()
+        .push(callback);
+}
+
+/// Same as [`on_object_destroy`], but only for instances of `class` (including subclasses)
+///
+/// Objects are matched against `class` while they're alive, since their class may be gone by the time
+/// they're destroyed.
+pub fn on_class_destroy(class: UClass, callback: fn(UObject)) {
+    CLASS_DESTROY_CALLBACKS
+        .lock()
+        .unwrap()
+        .entry(class.to_ptr() as usize)
+        .or_default()
+        .push(callback);
+}
+
+/// Compares the object array against the previous engine tick and fires the create/destroy callbacks
+pub(crate) fn poll_object_events() {
+    let create_callbacks = OBJECT_CREATE_CALLBACKS.lock().unwrap().clone();
+    let destroy_callbacks = OBJECT_DESTROY_CALLBACKS.lock().unwrap().clone();
+    let class_create_callbacks = CLASS_CREATE_CALLBACKS.lock().unwrap().clone();
+    let class_destroy_callbacks = CLASS_DESTROY_CALLBACKS.lock().unwrap().clone();
+
+    if create_callbacks.is_empty()
+        && destroy_callbacks.is_empty()
+        && class_create_callbacks.is_empty()
+        && class_destroy_callbacks.is_empty()
+    {
+        return;
+    }
+
+    let mut destroy_targets = class_destroy_callbacks.keys().copied().collect::<Vec<_>>();
+    destroy_targets.sort_unstable();
+
+    let previous = OBJECT_SNAPSHOT.lock().unwrap().take();
+    let first_poll = previous.is_none();
+    let rematch = previous
+        .as_ref()
+        .is_none_or(|previous| previous.destroy_targets != destroy_targets);
+    let mut remaining = previous
+        .map(|previous| previous.objects)
+        .unwrap_or_default();
+
+    let mut current = HashMap::new();
+
+    for object in FUObjectArray::get().iter() {
+        let ptr = object.to_ptr() as usize;
+
+        let targets = match remaining.remove(&ptr) {
+            // Seen before, only match again when the destroy callbacks changed
+            Some(targets) if !rematch => targets,
+            Some(_) => matching_targets(object, &destroy_targets),
+            None => {
+                if !first_poll {
+                    for callback in &create_callbacks {
+                        callback(object);
+                    }
+
+                    dispatch_class_create_callbacks(&class_create_callbacks, object);
+                }
+
+                matching_targets(object, &destroy_targets)
+            }
+        };
+
+        current.insert(ptr, targets);
+    }
+
+    // Whatever is left wasn't found in the object array anymore
+    for (object, targets) in remaining {
+        let object = UObject::from_ptr(object as *mut c_void);
+
+        for callback in &destroy_callbacks {
+            callback(object);
+        }
+
+        for callbacks in targets
+            .iter()
+            .flat_map(|target| class_destroy_callbacks.get(target))
+        {
+            for callback in callbacks {
+                callback(object);
+            }
+        }
+    }
+
+    *OBJECT_SNAPSHOT.lock().unwrap() = Some(ObjectSnapshot {
+        destroy_targets,
+        objects: current,
+    });
+}
+
+/// Returns the classes in `targets` that `object` is an instance of, `object` has to be alive
+fn matching_targets(object: UObject, targets: &[usize]) -> Vec<usize> {
+    if targets.is_empty() {
+        return vec![];
+    }
+
+    let Some(class) = object.get_class() else {
+        return vec![];
+    };
+
+    targets
+        .iter()
+        .copied()
+        .filter(|target| class.is_child_of(&UClass::from_ptr(*target as *mut c_void)))
+        .collect()
+}
+
+fn dispatch_class_create_callbacks(callbacks: &ClassCallbacks, object: UObject) {
+    if callbacks.is_empty() {
+        return;
+    }
+
+    let Some(class) = object.get_class() else {
+        return;
+    };
+
+    for (target, callbacks) in callbacks {
+        if !class.is_child_of(&UClass::from_ptr(*target as *mut c_void)) {
+            continue;
+        }
+
+        for callback in callbacks {
+            callback(object);
         }
+    }
+}
+
+fn initialize<'a>() -> &'a UEVR_UObjectHookFunctions {
+    let hook = load_function_table(&STATIC_OBJECT_HOOK, || super::API::get().sdk().uobject_hook);
+
+    unsafe { &*hook }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::mock;
+
+    #[test]
+    fn for_each_object_by_class_falls_back_past_the_stack_buffer() {
+        mock::install();
+
+        let mut objects = vec![0u64; 1500];
+        let objects = objects
+            .iter_mut()
+            .map(|object| object as *mut u64 as *mut c_void)
+            .collect::<Vec<_>>();
+        mock::set_class_objects(objects.len(), &objects);
+
+        let mut found = vec![];
+        for_each_object_by_class(UClass::from_ptr(objects[0]), false, |object| {
+            found.push(object.to_ptr())
+        });
 
-        &*STATIC_OBJECT_HOOK
+        assert_eq!(found, objects);
+        assert_eq!(mock::live_allocations(), 0);
     }
 }
@@ -0,0 +1,29 @@
+use crate::api::UClass;
+
+use std::{collections::HashMap, sync::LazyLock};
+
+type StaticClassFn = fn() -> Option<UClass>;
+
+/// Every type registered using [`crate::static_class`], collected at link time
+#[linkme::distributed_slice]
+pub static STATIC_CLASSES: [StaticClassEntry];
+
+/// Registered types by their static name, built on first use
+static CLASSES_BY_NAME: LazyLock<HashMap<&'static str, StaticClassFn>> = LazyLock::new(|| {
+    STATIC_CLASSES
+        .iter()
+        .map(|entry| (entry.name, entry.static_class))
+        .collect()
+});
+
+pub struct StaticClassEntry {
+    pub name: &'static str,
+    pub static_class: StaticClassFn,
+}
+
+/// Returns the class of the registered type with the static name `name`, e.g. `World`
+pub fn find_class_by_name(name: &str) -> Option<UClass> {
+    CLASSES_BY_NAME
+        .get(name)
+        .and_then(|static_class| static_class())
+}
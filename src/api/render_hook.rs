@@ -1,11 +1,13 @@
 use crate::{
+    api::load_function_table,
     bindings::{UEVR_FRenderTargetPoolHookFunctions, UEVR_IPooledRenderTargetHandle},
     util::encode_wstr,
 };
 
-use std::ptr::null;
+use std::{ptr::null_mut, sync::atomic::AtomicPtr};
 
-static mut STATIC_RENDER_HOOK: *const UEVR_FRenderTargetPoolHookFunctions = null();
+static STATIC_RENDER_HOOK: AtomicPtr<UEVR_FRenderTargetPoolHookFunctions> =
+    AtomicPtr::new(null_mut());
 
 pub fn activate() {
     let fun = initialize().activate.unwrap();
@@ -13,19 +15,35 @@ pub fn activate() {
     unsafe { fun() }
 }
 
-pub fn get_render_target(name: impl AsRef<str>) -> UEVR_IPooledRenderTargetHandle {
+/// Looks up a pooled render target by its name
+///
+/// UEVR has no way to enumerate the render target pool, so the name has to be known up front.
+/// The names of the pooled render targets can be found by running the `r.DumpRenderTargetPoolMemory`
+/// console command, which prints them to the game log.
+pub fn get_render_target(name: impl AsRef<str>) -> Option<UEVR_IPooledRenderTargetHandle> {
     let name = encode_wstr(name);
     let fun = initialize().get_render_target.unwrap();
+    let handle = unsafe { fun(name.as_ptr()) };
 
-    unsafe { fun(name.as_ptr()) }
+    if handle.is_null() {
+        None
+    } else {
+        Some(handle)
+    }
+}
+
+/// Activates the render target pool hook before looking up the render target
+pub fn activate_and_get_render_target(
+    name: impl AsRef<str>,
+) -> Option<UEVR_IPooledRenderTargetHandle> {
+    activate();
+    get_render_target(name)
 }
 
 fn initialize<'a>() -> &'a UEVR_FRenderTargetPoolHookFunctions {
-    unsafe {
-        if STATIC_RENDER_HOOK.is_null() {
-            STATIC_RENDER_HOOK = super::API::get().sdk().render_target_pool_hook;
-        }
+    let hook = load_function_table(&STATIC_RENDER_HOOK, || {
+        super::API::get().sdk().render_target_pool_hook
+    });
 
-        &*STATIC_RENDER_HOOK
-    }
+    unsafe { &*hook }
 }
@@ -1,8 +1,12 @@
-use crate::{api::FRHITexture2D, bindings::UEVR_FFakeStereoRenderingHookFunctions};
+use crate::{
+    api::{load_function_table, FRHITexture2D},
+    bindings::UEVR_FFakeStereoRenderingHookFunctions,
+};
 
-use std::ptr::null;
+use std::{ptr::null_mut, sync::atomic::AtomicPtr};
 
-static mut STATIC_STEREO_HOOK: *const UEVR_FFakeStereoRenderingHookFunctions = null();
+static STATIC_STEREO_HOOK: AtomicPtr<UEVR_FFakeStereoRenderingHookFunctions> =
+    AtomicPtr::new(null_mut());
 
 pub fn get_scene_render_target() -> FRHITexture2D {
     let fun = initialize().get_scene_render_target.unwrap();
@@ -16,12 +20,33 @@ pub fn get_ui_render_target() -> FRHITexture2D {
     unsafe { FRHITexture2D::from_handle(fun()) }
 }
 
-fn initialize<'a>() -> &'a UEVR_FFakeStereoRenderingHookFunctions {
+/// Checks whether UEVR provided the stereo hook functions
+pub fn is_active() -> bool {
     unsafe {
-        if STATIC_STEREO_HOOK.is_null() {
-            STATIC_STEREO_HOOK = super::API::get().sdk().stereo_hook;
-        }
-
-        &*STATIC_STEREO_HOOK
+        load_hook().as_ref().is_some_and(|hook| {
+            hook.get_scene_render_target.is_some() && hook.get_ui_render_target.is_some()
+        })
     }
 }
+
+/// Returns `None` if the stereo hook or the render target isn't available
+pub fn get_scene_render_target_size() -> Option<(u32, u32)> {
+    let fun = unsafe { load_hook().as_ref()?.get_scene_render_target? };
+
+    unsafe { FRHITexture2D::from_handle_safe(fun())?.get_size() }
+}
+
+/// Returns `None` if the stereo hook or the render target isn't available
+pub fn get_ui_render_target_size() -> Option<(u32, u32)> {
+    let fun = unsafe { load_hook().as_ref()?.get_ui_render_target? };
+
+    unsafe { FRHITexture2D::from_handle_safe(fun())?.get_size() }
+}
+
+fn initialize<'a>() -> &'a UEVR_FFakeStereoRenderingHookFunctions {
+    unsafe { &*load_hook() }
+}
+
+fn load_hook() -> *const UEVR_FFakeStereoRenderingHookFunctions {
+    load_function_table(&STATIC_STEREO_HOOK, || super::API::get().sdk().stereo_hook)
+}
@@ -0,0 +1,73 @@
+#[cfg(feature = "storage")]
+use std::path::Path;
+use std::{
+    fs,
+    io::{self, ErrorKind},
+    path::PathBuf,
+};
+
+/// Returns `<persistent dir>/plugins/<plugin_name>`, creating it if it doesn't exist yet
+pub fn plugin_data_dir(plugin_name: &str) -> io::Result<PathBuf> {
+    let persistent_dir = super::API::get().get_persistent_dir();
+    if persistent_dir.as_os_str().is_empty() {
+        return Err(io::Error::new(
+            ErrorKind::NotFound,
+            "UEVR did not return a persistent directory",
+        ));
+    }
+
+    let dir = persistent_dir.join("plugins").join(plugin_name);
+    fs::create_dir_all(&dir)?;
+
+    Ok(dir)
+}
+
+/// TOML files within the data directory of a plugin
+#[cfg(feature = "storage")]
+#[derive(Clone, Debug)]
+pub struct PluginStorage {
+    dir: PathBuf,
+}
+
+#[cfg(feature = "storage")]
+impl PluginStorage {
+    /// Opens the storage in [`plugin_data_dir`]
+    pub fn new(plugin_name: &str) -> io::Result<Self> {
+        Ok(Self {
+            dir: plugin_data_dir(plugin_name)?,
+        })
+    }
+
+    /// Opens the storage in `dir`, which is created when writing
+    pub fn from_dir(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Reads and parses `<name>.toml`
+    pub fn read_toml<T: serde::de::DeserializeOwned>(&self, name: &str) -> anyhow::Result<T> {
+        let path = self.path_of(name);
+        let contents = fs::read_to_string(&path)
+            .map_err(|e| anyhow::anyhow!("failed to read {}: {e}", path.display()))?;
+
+        toml::from_str(&contents)
+            .map_err(|e| anyhow::anyhow!("failed to parse {}: {e}", path.display()))
+    }
+
+    /// Serializes `val` into `<name>.toml`, replacing the file if it exists
+    pub fn write_toml<T: serde::Serialize>(&self, name: &str, val: &T) -> anyhow::Result<()> {
+        let path = self.path_of(name);
+        let contents = toml::to_string_pretty(val)?;
+
+        fs::create_dir_all(&self.dir)?;
+        fs::write(&path, contents)
+            .map_err(|e| anyhow::anyhow!("failed to write {}: {e}", path.display()))
+    }
+
+    fn path_of(&self, name: &str) -> PathBuf {
+        self.dir.join(format!("{name}.toml"))
+    }
+}
@@ -1,19 +1,67 @@
-use crate::bindings::{
-    UEVR_ActionHandle, UEVR_InputSourceHandle, UEVR_Matrix4x4f, UEVR_Quaternionf,
-    UEVR_TrackedDeviceIndex, UEVR_VRData, UEVR_Vector2f, UEVR_Vector3f,
+use crate::{
+    api::load_function_table,
+    bindings::{
+        UEVR_ActionHandle, UEVR_InputSourceHandle, UEVR_Matrix4x4f, UEVR_Quaternionf,
+        UEVR_TrackedDeviceIndex, UEVR_VRData, UEVR_Vector2f, UEVR_Vector3f, UEVR_Vector4f,
+    },
 };
 
 use std::{
-    ffi::{CStr, CString},
-    mem::{transmute, zeroed},
-    ptr::null,
+    cell::Cell,
+    collections::{HashMap, VecDeque},
+    ffi::{c_void, CStr, CString},
+    fmt,
+    mem::zeroed,
+    ptr::null_mut,
+    sync::{atomic::AtomicPtr, LazyLock, Mutex},
 };
 
-static mut STATIC_UEVR_VRDATA: *const UEVR_VRData = null();
+pub mod haptics;
 
-pub trait ModValue {
+static STATIC_UEVR_VRDATA: AtomicPtr<UEVR_VRData> = AtomicPtr::new(null_mut());
+
+/// Device index UEVR uses for devices that don't exist, e.g. a controller that isn't connected
+pub const INVALID_DEVICE_INDEX: UEVR_TrackedDeviceIndex = -1;
+
+/// Resolved action handles by name, shared between all [`Action`]s
+static ACTION_HANDLES: LazyLock<Mutex<HashMap<String, usize>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+pub trait ModValue: Sized {
     fn serialize(self) -> CString;
     fn deserialize(value: &CStr) -> Self;
+
+    /// Like [`ModValue::deserialize`], but fails on values that can't be parsed
+    fn try_deserialize(value: &CStr) -> Result<Self, ModValueError> {
+        Ok(Self::deserialize(value))
+    }
+}
+
+#[derive(Debug)]
+pub enum ModValueError {
+    /// UEVR has no value for the key
+    Missing { key: String },
+    /// The value couldn't be parsed as the requested type
+    Parse { value: String },
+}
+
+impl fmt::Display for ModValueError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Missing { key } => write!(f, "there is no mod value for {key}"),
+            Self::Parse { value } => write!(f, "cannot parse mod value {value:?}"),
+        }
+    }
+}
+
+impl std::error::Error for ModValueError {}
+
+impl ModValueError {
+    fn parse(value: &CStr) -> Self {
+        Self::Parse {
+            value: value.to_string_lossy().to_string(),
+        }
+    }
 }
 
 impl ModValue for String {
@@ -31,6 +79,14 @@ impl ModValue for bool {
         value.to_string_lossy().to_string() == "true"
     }
 
+    fn try_deserialize(value: &CStr) -> Result<Self, ModValueError> {
+        match value.to_bytes() {
+            b"true" => Ok(true),
+            b"false" => Ok(false),
+            _ => Err(ModValueError::parse(value)),
+        }
+    }
+
     fn serialize(self) -> CString {
         if self {
             CString::new("true").unwrap()
@@ -40,18 +96,529 @@ impl ModValue for bool {
     }
 }
 
+impl ModValue for f32 {
+    fn deserialize(value: &CStr) -> Self {
+        value.to_string_lossy().trim().parse().unwrap_or_default()
+    }
+
+    fn try_deserialize(value: &CStr) -> Result<Self, ModValueError> {
+        value
+            .to_string_lossy()
+            .trim()
+            .parse()
+            .map_err(|_| ModValueError::parse(value))
+    }
+
+    fn serialize(self) -> CString {
+        CString::new(self.to_string()).unwrap()
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
 pub struct Pose {
-    position: UEVR_Vector3f,
-    rotation: UEVR_Quaternionf,
+    pub position: UEVR_Vector3f,
+    pub rotation: UEVR_Quaternionf,
+}
+
+impl Pose {
+    /// Pose at the origin, with no rotation
+    pub const fn identity() -> Self {
+        Self {
+            position: UEVR_Vector3f {
+                x: 0.0,
+                y: 0.0,
+                z: 0.0,
+            },
+            rotation: UEVR_Quaternionf {
+                w: 1.0,
+                x: 0.0,
+                y: 0.0,
+                z: 0.0,
+            },
+        }
+    }
+
+    /// Pose at the origin with the given rotation
+    pub const fn from_rotation(rotation: UEVR_Quaternionf) -> Self {
+        Self {
+            position: Self::identity().position,
+            rotation,
+        }
+    }
+
+    /// Interpolates linearly between two poses, the rotation takes the shortest path
+    pub fn lerp(&self, other: &Pose, t: f32) -> Pose {
+        let a = &self.rotation;
+        let mut b = other.rotation;
+
+        if a.dot(&b) < 0.0 {
+            b = UEVR_Quaternionf {
+                w: -b.w,
+                x: -b.x,
+                y: -b.y,
+                z: -b.z,
+            };
+        }
+
+        let lerp = |from: f32, to: f32| from + (to - from) * t;
+
+        let rotation = UEVR_Quaternionf {
+            w: lerp(a.w, b.w),
+            x: lerp(a.x, b.x),
+            y: lerp(a.y, b.y),
+            z: lerp(a.z, b.z),
+        };
+
+        let length = rotation.dot(&rotation).sqrt();
+
+        Pose {
+            position: UEVR_Vector3f {
+                x: lerp(self.position.x, other.position.x),
+                y: lerp(self.position.y, other.position.y),
+                z: lerp(self.position.z, other.position.z),
+            },
+            rotation: if length > 0.0 {
+                UEVR_Quaternionf {
+                    w: rotation.w / length,
+                    x: rotation.x / length,
+                    y: rotation.y / length,
+                    z: rotation.z / length,
+                }
+            } else {
+                *a
+            },
+        }
+    }
+
+    /// Returns the transform of the pose as a row-major matrix, for row vectors like UE's `FMatrix`
+    ///
+    /// The first three rows are the rotated X, Y and Z axes, the last row is the position.
+    pub fn to_matrix(&self) -> UEVR_Matrix4x4f {
+        let [x, y, z] = [
+            UEVR_Vector3f::new(1.0, 0.0, 0.0),
+            UEVR_Vector3f::new(0.0, 1.0, 0.0),
+            UEVR_Vector3f::new(0.0, 0.0, 1.0),
+        ]
+        .map(|axis| self.rotation.rotate_vector(axis));
+        let p = &self.position;
+
+        UEVR_Matrix4x4f {
+            m: [
+                [x.x, x.y, x.z, 0.0],
+                [y.x, y.y, y.z, 0.0],
+                [z.x, z.y, z.z, 0.0],
+                [p.x, p.y, p.z, 1.0],
+            ],
+        }
+    }
+
+    /// Returns `self * other`, i.e. `other` relative to this pose moved into the space this pose is in
+    ///
+    /// `parent.compose(&child).transform_point(p)` equals `parent.transform_point(child.transform_point(p))`.
+    pub fn compose(&self, other: &Pose) -> Pose {
+        Pose {
+            position: self.transform_point(other.position),
+            rotation: self.rotation.multiply(&other.rotation),
+        }
+    }
+
+    /// Returns the pose that undoes this one, `pose.compose(&pose.inverse())` is the identity
+    pub fn inverse(&self) -> Pose {
+        let rotation = self.rotation.conjugate();
+
+        Pose {
+            position: rotation.rotate_vector(-self.position),
+            rotation,
+        }
+    }
+
+    /// Rotates and then translates a point from the local space of the pose into the space the pose is in
+    pub fn transform_point(&self, point: UEVR_Vector3f) -> UEVR_Vector3f {
+        self.transform_direction(point) + self.position
+    }
+
+    /// Rotates a direction from the local space of the pose, ignoring the position
+    pub fn transform_direction(&self, direction: UEVR_Vector3f) -> UEVR_Vector3f {
+        self.rotation.rotate_vector(direction)
+    }
+}
+
+impl Default for Pose {
+    fn default() -> Self {
+        Self::identity()
+    }
+}
+
+impl PartialEq for Pose {
+    fn eq(&self, other: &Self) -> bool {
+        let (a, b) = (&self.position, &other.position);
+        let (c, d) = (&self.rotation, &other.rotation);
+
+        a.x == b.x
+            && a.y == b.y
+            && a.z == b.z
+            && c.w == d.w
+            && c.x == d.x
+            && c.y == d.y
+            && c.z == d.z
+    }
+}
+
+/// Keeps the most recent poses of a device, to look up where it was at an earlier time
+///
+/// Call [`PoseHistory::record`] every frame, e.g. from `on_pre_engine_tick`.
+pub struct PoseHistory {
+    capacity: usize,
+    entries: VecDeque<(f64, Pose)>,
+}
+
+impl PoseHistory {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            entries: VecDeque::with_capacity(capacity.max(1)),
+        }
+    }
+
+    /// Adds a pose, dropping the oldest one when full. Timestamps must be increasing.
+    pub fn record(&mut self, timestamp: f64, pose: Pose) {
+        if let Some((last, _)) = self.entries.back() {
+            if timestamp <= *last {
+                return;
+            }
+        }
+
+        if self.entries.len() == self.capacity {
+            self.entries.pop_front();
+        }
+
+        self.entries.push_back((timestamp, pose));
+    }
+
+    /// Interpolates the pose at `t`, returning `None` if it's outside of the recorded time range
+    pub fn get_at_time(&self, t: f64) -> Option<Pose> {
+        let index = self
+            .entries
+            .partition_point(|(timestamp, _)| *timestamp < t);
+        let (next_time, next) = self.entries.get(index)?;
+
+        if *next_time == t {
+            return Some(*next);
+        }
+
+        let (prev_time, prev) = self.entries.get(index.checked_sub(1)?)?;
+        let alpha = (t - prev_time) / (next_time - prev_time);
+
+        Some(prev.lerp(next, alpha as f32))
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ActionHandle(UEVR_ActionHandle);
+
+impl ActionHandle {
+    pub fn get(name: &str) -> Self {
+        Self(get_action_handle(name))
+    }
+
+    pub fn raw(&self) -> UEVR_ActionHandle {
+        self.0
+    }
+}
+
+impl From<UEVR_ActionHandle> for ActionHandle {
+    fn from(value: UEVR_ActionHandle) -> Self {
+        Self(value)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct InputSourceHandle(UEVR_InputSourceHandle);
+
+impl InputSourceHandle {
+    pub fn left() -> Self {
+        Self(get_left_joystick_source())
+    }
+
+    pub fn right() -> Self {
+        Self(get_right_joystick_source())
+    }
+
+    pub fn raw(&self) -> UEVR_InputSourceHandle {
+        self.0
+    }
+}
+
+impl From<UEVR_InputSourceHandle> for InputSourceHandle {
+    fn from(value: UEVR_InputSourceHandle) -> Self {
+        Self(value)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Hand {
+    Left,
+    Right,
+}
+
+impl Hand {
+    /// The joystick input source of the controller in this hand
+    pub fn joystick_source(&self) -> InputSourceHandle {
+        match self {
+            Hand::Left => InputSourceHandle::left(),
+            Hand::Right => InputSourceHandle::right(),
+        }
+    }
+}
+
+/// Thumbstick of one controller
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Joystick(InputSourceHandle);
+
+impl Joystick {
+    pub fn left() -> Self {
+        Self(InputSourceHandle::left())
+    }
+
+    pub fn right() -> Self {
+        Self(InputSourceHandle::right())
+    }
+
+    pub fn from_hand(hand: Hand) -> Self {
+        Self(hand.joystick_source())
+    }
+
+    pub fn source(&self) -> InputSourceHandle {
+        self.0
+    }
+
+    /// Raw stick position, each axis in `-1.0..=1.0`
+    pub fn axis(&self) -> UEVR_Vector2f {
+        get_joystick_axis(self.0)
+    }
+
+    /// Stick position with a radial deadzone applied
+    ///
+    /// Positions inside `deadzone` return zero, the remaining range is rescaled so the
+    /// output still starts at 0 and reaches 1 at full deflection.
+    pub fn axis_with_deadzone(&self, deadzone: f32) -> UEVR_Vector2f {
+        let axis = self.axis();
+        let deadzone = deadzone.clamp(0.0, 1.0);
+        let length = axis.length();
+
+        if length <= deadzone || deadzone >= 1.0 {
+            return UEVR_Vector2f::ZERO;
+        }
+
+        let scaled = ((length - deadzone) / (1.0 - deadzone)).min(1.0);
+        axis * (scaled / length)
+    }
+
+    /// Whether `action` is active on this joystick
+    pub fn is_action_active(&self, action: &Action) -> bool {
+        action
+            .handle()
+            .is_some_and(|handle| is_action_active(handle, self.0))
+    }
+
+    /// Lowest XInput index used by the VR controllers
+    pub fn lowest_xinput_index() -> u32 {
+        get_lowest_xinput_index()
+    }
+}
+
+/// Action with a cached handle, resolved on first use
+///
+/// Handles are shared between all actions with the same name, so each name is only resolved once.
+pub struct Action {
+    name: String,
+    handle: Cell<Option<ActionHandle>>,
+}
+
+impl Action {
+    /// Creates the action, this is fine to do before the runtime is ready
+    pub fn new(name: &str) -> Action {
+        Action {
+            name: name.to_string(),
+            handle: Cell::new(None),
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the handle, or `None` if the runtime isn't ready or doesn't know the action
+    pub fn handle(&self) -> Option<ActionHandle> {
+        if let Some(handle) = self.handle.get() {
+            return Some(handle);
+        }
+
+        if !is_runtime_ready() {
+            return None;
+        }
+
+        let mut handles = ACTION_HANDLES.lock().unwrap();
+        let handle = match handles.get(&self.name) {
+            Some(handle) => *handle as UEVR_ActionHandle,
+            None => {
+                let handle = get_action_handle(&self.name);
+                if handle.is_null() {
+                    return None;
+                }
+
+                handles.insert(self.name.clone(), handle as usize);
+                handle
+            }
+        };
+
+        self.handle.set(Some(ActionHandle(handle)));
+        self.handle.get()
+    }
+
+    /// Whether the action is active on the joystick of `hand`
+    pub fn is_active(&self, hand: Hand) -> bool {
+        self.handle()
+            .is_some_and(|handle| is_action_active(handle, hand.joystick_source()))
+    }
+
+    /// Whether the action is active on any joystick
+    pub fn is_active_any(&self) -> bool {
+        self.handle()
+            .is_some_and(|handle| is_action_active_any_joystick(handle.raw()))
+    }
+}
+
+/// Tracks the state of an action across frames
+///
+/// Call [`ActionState::update`] once per frame, e.g. from `Plugin::on_pre_engine_tick`.
+pub struct ActionState {
+    handle: ActionHandle,
+    any_source: bool,
+    prev: bool,
+    curr: bool,
+}
+
+impl ActionState {
+    pub fn new(handle: ActionHandle) -> Self {
+        Self {
+            handle,
+            any_source: false,
+            prev: false,
+            curr: false,
+        }
+    }
+
+    /// Tracks the action on both joysticks, the source passed to [`ActionState::update`] is ignored
+    pub fn any_source(handle: ActionHandle) -> Self {
+        Self {
+            any_source: true,
+            ..Self::new(handle)
+        }
+    }
+
+    pub fn update(&mut self, source: InputSourceHandle) {
+        self.prev = self.curr;
+        self.curr = if self.any_source {
+            is_action_active_any_joystick(self.handle.raw())
+        } else {
+            is_action_active(self.handle, source)
+        };
+    }
+
+    pub fn is_held(&self) -> bool {
+        self.curr
+    }
+
+    pub fn was_just_pressed(&self) -> bool {
+        self.curr && !self.prev
+    }
+
+    pub fn was_just_released(&self) -> bool {
+        !self.curr && self.prev
+    }
+}
+
+/// Sequence of haptic pulses and pauses, played back using [`trigger_haptic_vibration`]
+#[derive(Clone, Default)]
+pub struct HapticPattern {
+    /// Delay (relative to the end of the previous step), amplitude, frequency and duration of every step
+    steps: Vec<(f32, f32, f32, f32)>,
+}
+
+impl HapticPattern {
+    pub fn pulse(amplitude: f32, frequency: f32, duration: f32) -> Self {
+        Self::default().then_pulse(amplitude, frequency, duration)
+    }
+
+    pub fn then_pause(mut self, delay: f32) -> Self {
+        self.steps.push((delay, 0.0, 0.0, 0.0));
+        self
+    }
+
+    pub fn then_pulse(mut self, amplitude: f32, frequency: f32, duration: f32) -> Self {
+        self.steps.push((0.0, amplitude, frequency, duration));
+        self
+    }
+
+    /// Schedules every pulse at once, each delayed by the duration of the steps before it
+    pub fn play(self, source: InputSourceHandle) {
+        let mut offset = 0.0;
+
+        for (delay, amplitude, frequency, duration) in self.steps {
+            offset += delay;
+
+            if amplitude > 0.0 {
+                trigger_haptic_vibration(offset, amplitude, frequency, duration, source);
+            }
+
+            offset += duration;
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TrackingState {
+    Untracked,
+    InertiaOnly,
+    Tracked,
 }
 
 #[repr(i32)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum Eye {
     Left,
     Right,
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Runtime {
+    OpenXR,
+    OpenVR,
+    None,
+}
+
+impl Runtime {
+    /// Whether any runtime is in use
+    pub fn is_any(&self) -> bool {
+        *self != Runtime::None
+    }
+}
+
 #[repr(i32)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum AimMethod {
     Game,
     Head,
@@ -61,22 +628,61 @@ pub enum AimMethod {
     TwoHandedLeft,
 }
 
+impl TryFrom<u32> for AimMethod {
+    type Error = u32;
+
+    fn try_from(value: u32) -> Result<Self, Self::Error> {
+        Ok(match value {
+            0 => AimMethod::Game,
+            1 => AimMethod::Head,
+            2 => AimMethod::RightController,
+            3 => AimMethod::LeftController,
+            4 => AimMethod::TwoHandedRight,
+            5 => AimMethod::TwoHandedLeft,
+            _ => return Err(value),
+        })
+    }
+}
+
+/// Converts a raw aim method, logging values this crate doesn't know about
+fn checked_aim_method(value: u32, source: &str) -> Result<AimMethod, u32> {
+    AimMethod::try_from(value).inspect_err(|value| {
+        crate::warn!("UEVR returned an unknown {source} {value}");
+    })
+}
+
 pub fn is_runtime_ready() -> bool {
     let fun = initialize().is_runtime_ready.unwrap();
 
     unsafe { fun() }
 }
 
-pub fn is_openvr() -> bool {
-    let fun = initialize().is_openvr.unwrap();
+/// Returns the VR runtime in use, or [`Runtime::None`] if it isn't ready
+pub fn get_runtime() -> Runtime {
+    if !is_runtime_ready() {
+        return Runtime::None;
+    }
 
-    unsafe { fun() }
+    let is_openxr = initialize().is_openxr.unwrap();
+    let is_openvr = initialize().is_openvr.unwrap();
+
+    unsafe {
+        if is_openxr() {
+            Runtime::OpenXR
+        } else if is_openvr() {
+            Runtime::OpenVR
+        } else {
+            Runtime::None
+        }
+    }
 }
 
-pub fn is_openxr() -> bool {
-    let fun = initialize().is_openxr.unwrap();
+pub fn is_openvr() -> bool {
+    get_runtime() == Runtime::OpenVR
+}
 
-    unsafe { fun() }
+pub fn is_openxr() -> bool {
+    get_runtime() == Runtime::OpenXR
 }
 
 pub fn is_hmd_active() -> bool {
@@ -113,6 +719,31 @@ pub fn set_rotation_offset(offset: &UEVR_Quaternionf) {
     unsafe { fun(offset) }
 }
 
+pub fn hmd_index() -> UEVR_TrackedDeviceIndex {
+    get_hmd_index()
+}
+
+/// Returns the tracked device index of the controller in `hand`
+pub fn controller_index(hand: Hand) -> UEVR_TrackedDeviceIndex {
+    match hand {
+        Hand::Left => get_left_controller_index(),
+        Hand::Right => get_right_controller_index(),
+    }
+}
+
+/// Returns the joystick input source of the controller in `hand`
+pub fn joystick_source(hand: Hand) -> InputSourceHandle {
+    hand.joystick_source()
+}
+
+pub fn get_aim_pose_hand(hand: Hand) -> Pose {
+    get_aim_pose(controller_index(hand))
+}
+
+pub fn get_grip_pose_hand(hand: Hand) -> Pose {
+    get_grip_pose(controller_index(hand))
+}
+
 pub fn get_hmd_index() -> UEVR_TrackedDeviceIndex {
     let fun = initialize().get_hmd_index.unwrap();
 
@@ -131,6 +762,17 @@ pub fn get_right_controller_index() -> UEVR_TrackedDeviceIndex {
     unsafe { fun() }
 }
 
+/// Returns the pose of a tracked device in VR space
+///
+/// ```no_run
+/// use rusty_uevr::api::vr;
+///
+/// let pose = vr::get_pose(vr::get_hmd_index());
+/// let head_height = pose.position.y;
+/// let head_rotation = pose.rotation;
+/// ```
+///
+/// The pose is zeroed if the runtime isn't ready or the index is invalid, use [`try_get_pose`] to tell those apart.
 pub fn get_pose(index: UEVR_TrackedDeviceIndex) -> Pose {
     let fun = initialize().get_pose.unwrap();
     let mut result = unsafe { zeroed::<Pose>() };
@@ -139,6 +781,69 @@ pub fn get_pose(index: UEVR_TrackedDeviceIndex) -> Pose {
     result
 }
 
+/// Returns the tracking state of a device
+///
+/// UEVR doesn't expose the pose validity reported by the runtime, so a device is considered tracked
+/// when the runtime is ready and reports a valid rotation for it. [`TrackingState::InertiaOnly`] is never returned.
+pub fn get_tracking_state(index: UEVR_TrackedDeviceIndex) -> TrackingState {
+    if index < 0 || !is_runtime_ready() || !is_hmd_active() {
+        return TrackingState::Untracked;
+    }
+
+    tracking_state_of(&get_pose(index))
+}
+
+fn tracking_state_of(pose: &Pose) -> TrackingState {
+    let rotation = &pose.rotation;
+    let length = rotation.w * rotation.w
+        + rotation.x * rotation.x
+        + rotation.y * rotation.y
+        + rotation.z * rotation.z;
+
+    if length > 0.5 {
+        TrackingState::Tracked
+    } else {
+        TrackingState::Untracked
+    }
+}
+
+/// Returns the indices of the tracked devices that are currently tracked
+///
+/// UEVR only exposes the HMD and both controllers, so other devices (e.g. full-body trackers) are not included.
+pub fn get_all_tracked_device_indices() -> Vec<UEVR_TrackedDeviceIndex> {
+    [
+        get_hmd_index(),
+        get_left_controller_index(),
+        get_right_controller_index(),
+    ]
+    .into_iter()
+    .filter(|index| get_tracking_state(*index) == TrackingState::Tracked)
+    .collect()
+}
+
+/// Returns the poses of all tracked devices, fetching each pose only once
+///
+/// Like [`get_all_tracked_device_indices`], only the HMD and both controllers are included.
+pub fn get_poses_batch() -> Vec<(UEVR_TrackedDeviceIndex, Pose)> {
+    if !is_runtime_ready() || !is_hmd_active() {
+        return vec![];
+    }
+
+    [
+        get_hmd_index(),
+        get_left_controller_index(),
+        get_right_controller_index(),
+    ]
+    .into_iter()
+    .filter(|index| *index >= 0)
+    .map(|index| (index, get_pose(index)))
+    .filter(|(_, pose)| tracking_state_of(pose) == TrackingState::Tracked)
+    .collect()
+}
+
+/// Returns the transform of a tracked device in VR space
+///
+/// The matrix is zeroed if the runtime isn't ready or the index is invalid, see [`try_get_transform`].
 pub fn get_transform(index: UEVR_TrackedDeviceIndex) -> UEVR_Matrix4x4f {
     let fun = initialize().get_transform.unwrap();
     let mut result = unsafe { zeroed() };
@@ -147,6 +852,16 @@ pub fn get_transform(index: UEVR_TrackedDeviceIndex) -> UEVR_Matrix4x4f {
     result
 }
 
+/// Returns the pose of the grip of a controller, i.e. where the hand holds it
+///
+/// ```no_run
+/// use rusty_uevr::api::vr;
+///
+/// let grip = vr::get_grip_pose(vr::get_right_controller_index());
+/// let hand_position = (grip.position.x, grip.position.y, grip.position.z);
+/// ```
+///
+/// The pose is zeroed if the runtime isn't ready or the index is invalid, see [`try_get_grip_pose`].
 pub fn get_grip_pose(index: UEVR_TrackedDeviceIndex) -> Pose {
     let fun = initialize().get_grip_pose.unwrap();
     let mut result = unsafe { zeroed::<Pose>() };
@@ -155,6 +870,16 @@ pub fn get_grip_pose(index: UEVR_TrackedDeviceIndex) -> Pose {
     result
 }
 
+/// Returns the pose a controller is aiming with, pointing down -Z
+///
+/// ```no_run
+/// use rusty_uevr::api::vr;
+///
+/// let aim = vr::get_aim_pose(vr::get_right_controller_index());
+/// let (origin, rotation) = (aim.position, aim.rotation);
+/// ```
+///
+/// The pose is zeroed if the runtime isn't ready or the index is invalid, see [`try_get_aim_pose`].
 pub fn get_aim_pose(index: UEVR_TrackedDeviceIndex) -> Pose {
     let fun = initialize().get_aim_pose.unwrap();
     let mut result = unsafe { zeroed::<Pose>() };
@@ -163,6 +888,69 @@ pub fn get_aim_pose(index: UEVR_TrackedDeviceIndex) -> Pose {
     result
 }
 
+/// Whether UEVR has actual data for the device, rather than returning zeroes
+fn has_device_data(index: UEVR_TrackedDeviceIndex) -> bool {
+    // Covers INVALID_DEVICE_INDEX as well
+    if index < 0 || !is_runtime_ready() {
+        return false;
+    }
+
+    index != get_hmd_index() || is_hmd_active()
+}
+
+/// Same as [`get_pose`], but returns `None` if the runtime isn't ready, the HMD isn't active or the index is invalid
+pub fn try_get_pose(index: UEVR_TrackedDeviceIndex) -> Option<Pose> {
+    has_device_data(index).then(|| get_pose(index))
+}
+
+/// Same as [`get_transform`], but returns `None` if the runtime isn't ready, the HMD isn't active or the index is invalid
+pub fn try_get_transform(index: UEVR_TrackedDeviceIndex) -> Option<UEVR_Matrix4x4f> {
+    has_device_data(index).then(|| get_transform(index))
+}
+
+/// Same as [`get_grip_pose`], but returns `None` if the runtime isn't ready or the index is invalid
+pub fn try_get_grip_pose(index: UEVR_TrackedDeviceIndex) -> Option<Pose> {
+    has_device_data(index).then(|| get_grip_pose(index))
+}
+
+/// Same as [`get_aim_pose`], but returns `None` if the runtime isn't ready or the index is invalid
+pub fn try_get_aim_pose(index: UEVR_TrackedDeviceIndex) -> Option<Pose> {
+    has_device_data(index).then(|| get_aim_pose(index))
+}
+
+/// Distance in meters below which [`two_handed_aim_pose`] falls back to the dominant hand alone
+const TWO_HANDED_MIN_DISTANCE: f32 = 0.05;
+
+/// Computes an aim pose held with two hands, pointing from the dominant to the support controller
+///
+/// The position is the grip of the dominant controller. If the controllers are within 5 cm of
+/// each other the aim pose of the dominant controller is returned.
+pub fn two_handed_aim_pose(
+    dominant: UEVR_TrackedDeviceIndex,
+    support: UEVR_TrackedDeviceIndex,
+) -> Pose {
+    let dominant_aim = get_aim_pose(dominant);
+    let support_aim = get_aim_pose(support);
+
+    let delta = support_aim.position - dominant_aim.position;
+    let distance = delta.length();
+
+    if distance < TWO_HANDED_MIN_DISTANCE {
+        return dominant_aim;
+    }
+
+    // Controllers point down -Z in VR space
+    let forward = dominant_aim
+        .rotation
+        .rotate_vector(UEVR_Vector3f::new(0.0, 0.0, -1.0));
+    let alignment = UEVR_Quaternionf::rotation_between(forward, delta / distance);
+
+    Pose {
+        position: get_grip_pose(dominant).position,
+        rotation: alignment.multiply(&dominant_aim.rotation),
+    }
+}
+
 pub fn get_grip_transform(index: UEVR_TrackedDeviceIndex) -> UEVR_Matrix4x4f {
     let fun = initialize().get_grip_transform.unwrap();
     let mut result = unsafe { zeroed() };
@@ -195,6 +983,101 @@ pub fn get_ue_projection_matrix(eye: Eye) -> UEVR_Matrix4x4f {
     result
 }
 
+/// Frustum of an eye, extracted from its UE projection matrix
+///
+/// The tangents are those of the angles between the view direction and each side of the frustum,
+/// `tan_left` and `tan_down` are negative unless the frustum doesn't contain the view direction.
+#[derive(Clone, Copy, Debug)]
+pub struct EyeProjection {
+    pub matrix: UEVR_Matrix4x4f,
+    pub tan_left: f32,
+    pub tan_right: f32,
+    pub tan_up: f32,
+    pub tan_down: f32,
+    pub near: f32,
+}
+
+impl EyeProjection {
+    /// Extracts the frustum from a UE projection matrix, which is reversed-Z and usually has an infinite far plane
+    pub fn from_matrix(matrix: UEVR_Matrix4x4f) -> Self {
+        let m = &matrix.m;
+
+        // Row 0 and 1 map the tangents to -1..1: x * 2 / (r - l) - z * (r + l) / (r - l)
+        let tan_left = (-1.0 - m[2][0]) / m[0][0];
+        let tan_right = (1.0 - m[2][0]) / m[0][0];
+        let tan_down = (-1.0 - m[2][1]) / m[1][1];
+        let tan_up = (1.0 - m[2][1]) / m[1][1];
+
+        // Depth is m22 + m32 / z, which is 1 at the near plane. With an infinite far plane m22 is 0.
+        let near = m[3][2] / (1.0 - m[2][2]);
+
+        Self {
+            matrix,
+            tan_left,
+            tan_right,
+            tan_up,
+            tan_down,
+            near,
+        }
+    }
+
+    /// Horizontal field of view in degrees
+    pub fn horizontal_fov(&self) -> f32 {
+        (self.tan_right.atan() - self.tan_left.atan()).to_degrees()
+    }
+
+    /// Vertical field of view in degrees
+    pub fn vertical_fov(&self) -> f32 {
+        (self.tan_up.atan() - self.tan_down.atan()).to_degrees()
+    }
+}
+
+pub fn get_eye_projection(eye: Eye) -> EyeProjection {
+    EyeProjection::from_matrix(get_ue_projection_matrix(eye))
+}
+
+/// Returns the projection of the left and right eye, in that order
+pub fn get_both_eyes() -> [EyeProjection; 2] {
+    [Eye::Left, Eye::Right].map(get_eye_projection)
+}
+
+/// Projects a position in VR tracking space onto the render target of an eye
+///
+/// Returns pixel coordinates within [`get_hmd_width`] and [`get_hmd_height`], or `None` if the
+/// position is behind the eye.
+pub fn world_to_screen(world_pos: UEVR_Vector3f, eye: Eye) -> Option<UEVR_Vector2f> {
+    let hmd = get_transform(get_hmd_index());
+    let offset = get_eye_offset(eye);
+
+    // The eye has the rotation of the HMD, and is offset in its local space
+    let relative = world_pos - hmd.transform_point(offset);
+
+    // Rotate into view space using the inverse (transposed) rotation of the HMD
+    let view = [0, 1, 2].map(|axis| {
+        UEVR_Vector3f::new(hmd.m[axis][0], hmd.m[axis][1], hmd.m[axis][2]).dot(&relative)
+    });
+
+    // VR space looks down -Z, while the UE projection expects +Z to be forward
+    let clip = get_ue_projection_matrix(eye).transform_vector4(UEVR_Vector4f {
+        x: view[0],
+        y: view[1],
+        z: -view[2],
+        w: 1.0,
+    });
+
+    if clip.w <= 0.0 {
+        return None;
+    }
+
+    let ndc_x = clip.x / clip.w;
+    let ndc_y = clip.y / clip.w;
+
+    Some(UEVR_Vector2f {
+        x: (ndc_x * 0.5 + 0.5) * get_hmd_width() as f32,
+        y: (0.5 - ndc_y * 0.5) * get_hmd_height() as f32,
+    })
+}
+
 pub fn get_left_joystick_source() -> UEVR_InputSourceHandle {
     let fun = initialize().get_left_joystick_source.unwrap();
 
@@ -214,10 +1097,10 @@ pub fn get_action_handle(name: impl AsRef<str>) -> UEVR_ActionHandle {
     unsafe { fun(name.as_ptr()) }
 }
 
-pub fn is_action_active(handle: UEVR_ActionHandle, source: UEVR_InputSourceHandle) -> bool {
+pub fn is_action_active(handle: ActionHandle, source: InputSourceHandle) -> bool {
     let fun = initialize().is_action_active.unwrap();
 
-    unsafe { fun(handle, source) }
+    unsafe { fun(handle.0, source.0) }
 }
 
 pub fn is_action_active_any_joystick(handle: UEVR_ActionHandle) -> bool {
@@ -226,11 +1109,11 @@ pub fn is_action_active_any_joystick(handle: UEVR_ActionHandle) -> bool {
     unsafe { fun(handle) }
 }
 
-pub fn get_joystick_axis(source: UEVR_InputSourceHandle) -> UEVR_Vector2f {
+pub fn get_joystick_axis(source: InputSourceHandle) -> UEVR_Vector2f {
     let fun = initialize().get_joystick_axis.unwrap();
     let mut result = unsafe { zeroed() };
 
-    unsafe { fun(source, &mut result) }
+    unsafe { fun(source.0, &mut result) }
     result
 }
 
@@ -239,11 +1122,16 @@ pub fn trigger_haptic_vibration(
     amplitude: f32,
     frequency: f32,
     duration: f32,
-    source: UEVR_InputSourceHandle,
+    source: InputSourceHandle,
 ) {
     let fun = initialize().trigger_haptic_vibration.unwrap();
 
-    unsafe { fun(delay, amplitude, frequency, duration, source) }
+    unsafe { fun(delay, duration, frequency, amplitude, source.0) }
+}
+
+/// Same as [`trigger_haptic_vibration`], for the controller in `hand`
+pub fn trigger_haptics(hand: Hand, delay: f32, amplitude: f32, frequency: f32, duration: f32) {
+    trigger_haptic_vibration(delay, amplitude, frequency, duration, joystick_source(hand))
 }
 
 pub fn is_using_controllers() -> bool {
@@ -252,10 +1140,11 @@ pub fn is_using_controllers() -> bool {
     unsafe { fun() }
 }
 
-pub fn get_movement_orientation() -> AimMethod {
+/// Returns the movement orientation, or the raw value if it isn't a known [`AimMethod`]
+pub fn get_movement_orientation() -> Result<AimMethod, u32> {
     let fun = initialize().get_movement_orientation.unwrap();
 
-    unsafe { transmute(fun()) }
+    checked_aim_method(unsafe { fun() }, "movement orientation")
 }
 
 pub fn get_lowest_xinput_index() -> u32 {
@@ -276,10 +1165,95 @@ pub fn recenter_horizon() {
     unsafe { fun() }
 }
 
-pub fn get_aim_method() -> AimMethod {
+/// Moves the VR stage to a game-defined position and rotation
+pub fn recenter_to(position: UEVR_Vector3f, rotation: UEVR_Quaternionf) {
+    set_standing_origin(&position);
+    set_rotation_offset(&rotation);
+}
+
+/// Moves the standing origin and rotation offset so the HMD ends up at `pose`
+///
+/// Unlike [`recenter_to`], the values are computed from the current HMD pose. `pose` is in the VR space
+/// UEVR renders from, where the view is placed at `rotation_offset * (hmd_position - standing_origin)`.
+/// Only the yaw of `pose.rotation` is used, so the horizon stays level. Does nothing if the HMD isn't active.
+pub fn recenter_hmd_to(pose: &Pose) {
+    let Some(hmd) = current_hmd_pose("recenter") else {
+        return;
+    };
+
+    let offset = yaw_rotation(yaw_of(&pose.rotation) - yaw_of(&hmd.rotation));
+    let origin = hmd.position
+        - Pose::from_rotation(offset)
+            .inverse()
+            .transform_direction(pose.position);
+
+    set_standing_origin(&origin);
+    set_rotation_offset(&offset);
+}
+
+/// Sets the rotation offset to a rotation of `degrees` around the vertical axis, positive angles turn to the right
+///
+/// The standing origin is moved so the view turns around the HMD instead of the origin. Does nothing if the HMD
+/// isn't active.
+pub fn set_yaw_offset(degrees: f32) {
+    let Some(hmd) = current_hmd_pose("set the yaw offset") else {
+        return;
+    };
+
+    let previous = Pose::from_rotation(get_rotation_offset());
+    let position = previous.transform_direction(hmd.position - get_standing_origin());
+
+    // The VR space is Y-up and right-handed, so turning right is a negative rotation around Y
+    let offset = yaw_rotation(-degrees.to_radians());
+    let origin = hmd.position
+        - Pose::from_rotation(offset)
+            .inverse()
+            .transform_direction(position);
+
+    set_standing_origin(&origin);
+    set_rotation_offset(&offset);
+}
+
+/// Resets the standing origin to zero and the rotation offset to identity
+pub fn reset_origin() {
+    let Pose { position, rotation } = Pose::identity();
+
+    recenter_to(position, rotation)
+}
+
+fn current_hmd_pose(action: &str) -> Option<Pose> {
+    if !is_hmd_active() {
+        crate::warn!("Cannot {action}, the HMD isn't active");
+        return None;
+    }
+
+    Some(get_pose(get_hmd_index()))
+}
+
+/// Counter-clockwise angle around Y, in radians, between -Z and the forward direction of `rotation`
+fn yaw_of(rotation: &UEVR_Quaternionf) -> f32 {
+    let forward = rotation.rotate_vector(UEVR_Vector3f::new(0.0, 0.0, -1.0));
+
+    (-forward.x).atan2(-forward.z)
+}
+
+/// Counter-clockwise rotation of `radians` around Y
+fn yaw_rotation(radians: f32) -> UEVR_Quaternionf {
+    let half_angle = radians / 2.0;
+
+    UEVR_Quaternionf {
+        x: 0.0,
+        y: half_angle.sin(),
+        z: 0.0,
+        w: half_angle.cos(),
+    }
+}
+
+/// Returns the aim method, or the raw value if it isn't a known [`AimMethod`]
+pub fn get_aim_method() -> Result<AimMethod, u32> {
     let fun = initialize().get_aim_method.unwrap();
 
-    unsafe { transmute(fun()) }
+    checked_aim_method(unsafe { fun() }, "aim method")
 }
 
 pub fn set_aim_method(method: AimMethod) {
@@ -324,6 +1298,49 @@ pub fn get_ui_height() -> u32 {
     unsafe { fun() }
 }
 
+/// Index of `IVRSystem::GetFloatTrackedDeviceProperty` in the vtable of IVRSystem_022
+const OPENVR_GET_FLOAT_PROPERTY_INDEX: usize = 22;
+
+/// `vr::Prop_DisplayFrequency_Float`
+const OPENVR_PROP_DISPLAY_FREQUENCY: i32 = 2002;
+
+type OpenVRGetFloatProperty = unsafe extern "C" fn(*mut c_void, u32, i32, *mut i32) -> f32;
+
+/// Returns the refresh rate of the HMD in Hz, or 0 if it's unknown
+///
+/// UEVR doesn't expose the refresh rate, so it's queried from the OpenVR system directly.
+/// OpenXR isn't supported, as UEVR doesn't enable the display refresh rate extension.
+pub fn get_refresh_rate() -> f32 {
+    if !is_runtime_ready() || !is_openvr() {
+        return 0.0;
+    }
+
+    let get_vr_system = unsafe { (*super::API::get().param().openvr).get_vr_system.unwrap() };
+    let system = unsafe { get_vr_system() } as *mut c_void;
+    if system.is_null() {
+        return 0.0;
+    }
+
+    let mut error = 0;
+    let rate = unsafe {
+        let vtable = *(system as *const *const OpenVRGetFloatProperty);
+        let fun = *vtable.add(OPENVR_GET_FLOAT_PROPERTY_INDEX);
+
+        fun(
+            system,
+            get_hmd_index() as _,
+            OPENVR_PROP_DISPLAY_FREQUENCY,
+            &mut error,
+        )
+    };
+
+    if error != 0 {
+        return 0.0;
+    }
+
+    rate
+}
+
 pub fn is_snap_turn_enabled() -> bool {
     let fun = initialize().is_snap_turn_enabled.unwrap();
 
@@ -336,6 +1353,40 @@ pub fn set_snap_turn_enabled(enabled: bool) {
     unsafe { fun(enabled) }
 }
 
+/// Default angle used by [`snap_turn_left`] and [`snap_turn_right`]
+pub const DEFAULT_SNAP_TURN_ANGLE: f32 = 45.0;
+
+/// Rotates the view around the vertical axis, positive angles turn to the right
+///
+/// Does nothing if snap turning is disabled in UEVR.
+pub fn snap_turn(angle_degrees: f32) {
+    if !is_snap_turn_enabled() {
+        crate::info!("Ignoring snap turn of {angle_degrees} degrees, snap turning is disabled");
+        return;
+    }
+
+    // The VR space is Y-up and right-handed, so turning right is a negative rotation around Y
+    let half_angle = -angle_degrees.to_radians() / 2.0;
+    let yaw = UEVR_Quaternionf {
+        x: 0.0,
+        y: half_angle.sin(),
+        z: 0.0,
+        w: half_angle.cos(),
+    };
+
+    let offset = yaw.multiply(&get_rotation_offset());
+
+    set_rotation_offset(&offset)
+}
+
+pub fn snap_turn_left() {
+    snap_turn(-DEFAULT_SNAP_TURN_ANGLE)
+}
+
+pub fn snap_turn_right() {
+    snap_turn(DEFAULT_SNAP_TURN_ANGLE)
+}
+
 pub fn is_decoupled_pitch_enabled() -> bool {
     let fun = initialize().is_decoupled_pitch_enabled.unwrap();
 
@@ -348,6 +1399,79 @@ pub fn set_decoupled_pitch_enabled(enabled: bool) {
     unsafe { fun(enabled) }
 }
 
+/// The comfort settings of the user that affect movement, read and written together
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MovementSettings {
+    pub snap_turn_enabled: bool,
+    pub decoupled_pitch_enabled: bool,
+    /// Unknown aim methods are kept as their raw value, so they're restored as-is
+    pub aim_method: Result<AimMethod, u32>,
+    pub aim_allowed: bool,
+}
+
+impl MovementSettings {
+    /// Reads the current settings
+    pub fn capture() -> Self {
+        Self {
+            snap_turn_enabled: is_snap_turn_enabled(),
+            decoupled_pitch_enabled: is_decoupled_pitch_enabled(),
+            aim_method: get_aim_method(),
+            aim_allowed: is_aim_allowed(),
+        }
+    }
+
+    /// Writes every setting back
+    pub fn apply(&self) {
+        set_snap_turn_enabled(self.snap_turn_enabled);
+        set_decoupled_pitch_enabled(self.decoupled_pitch_enabled);
+        set_aim_allowed(self.aim_allowed);
+
+        let aim_method = match self.aim_method {
+            Ok(method) => method as u32,
+            Err(raw) => raw,
+        };
+
+        let fun = initialize().set_aim_method.unwrap();
+        unsafe { fun(aim_method) }
+    }
+}
+
+/// Applies movement settings until dropped, then restores the settings that were active before
+///
+/// ```no_run
+/// use rusty_uevr::api::vr::{MovementSettings, ScopedMovementOverride};
+///
+/// let cutscene = MovementSettings {
+///     snap_turn_enabled: false,
+///     ..MovementSettings::capture()
+/// };
+/// let _guard = ScopedMovementOverride::new(cutscene);
+/// ```
+#[must_use = "the previous settings are restored as soon as the override is dropped"]
+pub struct ScopedMovementOverride {
+    previous: MovementSettings,
+}
+
+impl ScopedMovementOverride {
+    pub fn new(settings: MovementSettings) -> Self {
+        let previous = MovementSettings::capture();
+        settings.apply();
+
+        Self { previous }
+    }
+
+    /// The settings that will be restored
+    pub fn previous(&self) -> &MovementSettings {
+        &self.previous
+    }
+}
+
+impl Drop for ScopedMovementOverride {
+    fn drop(&mut self) {
+        self.previous.apply();
+    }
+}
+
 pub fn set_mod_value<T: ModValue>(key: impl AsRef<str>, value: T) {
     let fun = initialize().set_mod_value.unwrap();
     let key = CString::new(key.as_ref()).unwrap();
@@ -355,17 +1479,41 @@ pub fn set_mod_value<T: ModValue>(key: impl AsRef<str>, value: T) {
     unsafe { fun(key.as_ptr(), value.serialize().as_ptr()) }
 }
 
+/// Reads a mod value, missing keys are deserialized from an empty string
 pub fn get_mod_value<T: ModValue>(key: impl AsRef<str>) -> T {
+    match read_mod_value(key.as_ref()) {
+        Some(value) => T::deserialize(&value),
+        None => T::deserialize(c""),
+    }
+}
+
+/// Reads a mod value, failing if the key is missing or the value can't be parsed
+pub fn try_get_mod_value<T: ModValue>(key: impl AsRef<str>) -> Result<T, ModValueError> {
+    let key = key.as_ref();
+    let value = read_mod_value(key).ok_or_else(|| ModValueError::Missing {
+        key: key.to_string(),
+    })?;
+
+    T::try_deserialize(&value)
+}
+
+/// Returns `None` if UEVR has no value for the key
+fn read_mod_value(key: &str) -> Option<CString> {
+    // UEVR doesn't write anything into the buffer for missing keys, which is detected using a
+    // marker that can't be the start of a valid value followed by a terminator
+    const MARKER: i8 = 1;
+
     let fun = initialize().get_mod_value.unwrap();
-    let key = CString::new(key.as_ref()).unwrap();
+    let key = CString::new(key).unwrap();
     let mut result = [0; 256];
+    result[0] = MARKER;
 
     let str = unsafe {
         fun(key.as_ptr(), result.as_mut_ptr(), 256);
         CStr::from_ptr(result.as_ptr())
     };
 
-    T::deserialize(str)
+    (str.to_bytes() != [MARKER as u8]).then(|| str.to_owned())
 }
 
 pub fn save_config() {
@@ -381,11 +1529,151 @@ pub fn reload_config() {
 }
 
 fn initialize<'a>() -> &'a UEVR_VRData {
-    unsafe {
-        if STATIC_UEVR_VRDATA.is_null() {
-            STATIC_UEVR_VRDATA = super::API::get().param().vr;
+    let vr = load_function_table(&STATIC_UEVR_VRDATA, || super::API::get().param().vr);
+
+    unsafe { &*vr }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(a: UEVR_Vector3f, b: UEVR_Vector3f) {
+        assert!((a - b).length() < 1e-4, "{a:?} != {b:?}");
+    }
+
+    /// Rotated 90 degrees around Z (yaw) and moved away from the origin
+    fn yawed_pose() -> Pose {
+        let half = std::f32::consts::FRAC_1_SQRT_2;
+
+        Pose {
+            position: UEVR_Vector3f::new(10.0, 20.0, 30.0),
+            rotation: UEVR_Quaternionf {
+                w: half,
+                x: 0.0,
+                y: 0.0,
+                z: half,
+            },
         }
+    }
+
+    #[test]
+    fn pose_transforms_points() {
+        let pose = yawed_pose();
+
+        assert_close(
+            pose.transform_direction(UEVR_Vector3f::new(1.0, 0.0, 0.0)),
+            UEVR_Vector3f::new(0.0, 1.0, 0.0),
+        );
+        assert_close(
+            pose.transform_point(UEVR_Vector3f::new(1.0, 0.0, 0.0)),
+            UEVR_Vector3f::new(10.0, 21.0, 30.0),
+        );
+    }
+
+    #[test]
+    fn pose_inverse_undoes_pose() {
+        let pose = yawed_pose();
+        let point = UEVR_Vector3f::new(-3.0, 5.0, 7.0);
+
+        assert_close(
+            pose.inverse().transform_point(pose.transform_point(point)),
+            point,
+        );
+
+        let identity = pose.compose(&pose.inverse());
+        assert_close(identity.position, UEVR_Vector3f::ZERO);
+        assert!((identity.rotation.w.abs() - 1.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn pose_compose_applies_child_first() {
+        let parent = yawed_pose();
+        let child = Pose {
+            position: UEVR_Vector3f::new(1.0, 2.0, 3.0),
+            rotation: parent.rotation,
+        };
+        let point = UEVR_Vector3f::new(4.0, -1.0, 2.0);
+
+        assert_close(
+            parent.compose(&child).transform_point(point),
+            parent.transform_point(child.transform_point(point)),
+        );
+    }
+
+    #[test]
+    fn pose_matrix_matches_transform() {
+        let pose = yawed_pose();
+        let point = UEVR_Vector3f::new(4.0, -1.0, 2.0);
+
+        assert_close(
+            pose.to_matrix().transform_point(point),
+            pose.transform_point(point),
+        );
+        assert_eq!(pose.to_matrix().m[3], [10.0, 20.0, 30.0, 1.0]);
+    }
+
+    #[test]
+    fn pose_lerp_hits_endpoints() {
+        let (a, b) = (Pose::identity(), yawed_pose());
+
+        assert_eq!(a.lerp(&b, 0.0), a);
+        assert_close(a.lerp(&b, 1.0).position, b.position);
+        assert_close(
+            a.lerp(&b, 0.5).position,
+            UEVR_Vector3f::new(5.0, 10.0, 15.0),
+        );
+    }
+
+    /// Reversed-Z projection like UE builds it, `far` of `None` is an infinite far plane
+    fn projection(
+        left: f32,
+        right: f32,
+        down: f32,
+        up: f32,
+        near: f32,
+        far: Option<f32>,
+    ) -> UEVR_Matrix4x4f {
+        let (depth, depth_offset) = match far {
+            Some(far) => (-near / (far - near), near * far / (far - near)),
+            None => (0.0, near),
+        };
+
+        UEVR_Matrix4x4f {
+            m: [
+                [2.0 / (right - left), 0.0, 0.0, 0.0],
+                [0.0, 2.0 / (up - down), 0.0, 0.0],
+                [
+                    -(right + left) / (right - left),
+                    -(up + down) / (up - down),
+                    depth,
+                    1.0,
+                ],
+                [0.0, 0.0, depth_offset, 0.0],
+            ],
+        }
+    }
+
+    #[test]
+    fn eye_projection_from_off_center_matrix() {
+        let eye = EyeProjection::from_matrix(projection(-1.2, 0.8, -1.1, 0.9, 10.0, None));
+
+        assert!((eye.tan_left + 1.2).abs() < 1e-5);
+        assert!((eye.tan_right - 0.8).abs() < 1e-5);
+        assert!((eye.tan_down + 1.1).abs() < 1e-5);
+        assert!((eye.tan_up - 0.9).abs() < 1e-5);
+        assert!((eye.near - 10.0).abs() < 1e-4);
+
+        let fov = 0.8f32.atan().to_degrees() + 1.2f32.atan().to_degrees();
+        assert!((eye.horizontal_fov() - fov).abs() < 1e-3);
+    }
+
+    #[test]
+    fn eye_projection_with_finite_far_plane() {
+        let eye = EyeProjection::from_matrix(projection(-1.0, 1.0, -1.0, 1.0, 10.0, Some(1000.0)));
 
-        &*STATIC_UEVR_VRDATA
+        assert!((eye.near - 10.0).abs() < 1e-3);
+        assert!((eye.horizontal_fov() - 90.0).abs() < 1e-3);
+        assert!((eye.vertical_fov() - 90.0).abs() < 1e-3);
     }
 }
@@ -0,0 +1,113 @@
+use super::{trigger_haptics, Hand};
+
+use std::{collections::VecDeque, sync::Mutex};
+
+pub use super::HapticPattern;
+
+/// Frequency used by [`pulse`] and [`pulse_both`], in Hz
+pub const DEFAULT_FREQUENCY: f32 = 160.0;
+
+/// Patterns scheduled with [`HapticPattern::schedule`], advanced before every engine tick
+static SCHEDULED_PATTERNS: Mutex<Vec<ScheduledPattern>> = Mutex::new(Vec::new());
+
+struct ScheduledPattern {
+    /// `None` plays the pattern on both hands
+    hand: Option<Hand>,
+    /// Start time (relative to scheduling), amplitude, frequency and duration of every pulse
+    pulses: VecDeque<(f32, f32, f32, f32)>,
+    elapsed: f32,
+}
+
+/// Vibrates the controller in `hand` right away
+///
+/// `strength` is clamped to `0..=1`, calls with a zero duration do nothing.
+pub fn pulse(hand: Hand, strength: f32, duration: f32) {
+    pulse_with_frequency(hand, strength, DEFAULT_FREQUENCY, duration);
+}
+
+/// Same as [`pulse`], for both controllers
+pub fn pulse_both(strength: f32, duration: f32) {
+    pulse(Hand::Left, strength, duration);
+    pulse(Hand::Right, strength, duration);
+}
+
+/// Same as [`pulse`], with a custom frequency
+pub fn pulse_with_frequency(hand: Hand, strength: f32, frequency: f32, duration: f32) {
+    let strength = strength.clamp(0.0, 1.0);
+
+    if duration <= 0.0 || duration.is_nan() || strength == 0.0 || strength.is_nan() {
+        return;
+    }
+
+    trigger_haptics(hand, 0.0, strength, frequency, duration);
+}
+
+impl HapticPattern {
+    /// Two short pulses, meant for notifications
+    pub fn double_buzz() -> Self {
+        Self::pulse(0.6, DEFAULT_FREQUENCY, 0.05)
+            .then_pause(0.08)
+            .then_pulse(0.6, DEFAULT_FREQUENCY, 0.05)
+    }
+
+    /// Plays the pattern on the controller in `hand`, pulse by pulse from the game thread
+    pub fn schedule(self, hand: Hand) {
+        self.schedule_on(Some(hand));
+    }
+
+    /// Same as [`HapticPattern::schedule`], for both controllers
+    pub fn schedule_both(self) {
+        self.schedule_on(None);
+    }
+
+    fn schedule_on(self, hand: Option<Hand>) {
+        let mut offset = 0.0;
+        let mut pulses = VecDeque::new();
+
+        for (delay, amplitude, frequency, duration) in self.steps {
+            offset += delay;
+
+            if amplitude > 0.0 {
+                pulses.push_back((offset, amplitude, frequency, duration));
+            }
+
+            offset += duration;
+        }
+
+        if pulses.is_empty() {
+            return;
+        }
+
+        SCHEDULED_PATTERNS.lock().unwrap().push(ScheduledPattern {
+            hand,
+            pulses,
+            elapsed: 0.0,
+        });
+    }
+}
+
+/// Fires every scheduled pulse that is due, only called from the game thread
+pub(crate) fn tick(delta: f32) {
+    let mut patterns = SCHEDULED_PATTERNS.lock().unwrap();
+
+    patterns.retain_mut(|pattern| {
+        while let Some(&(start, amplitude, frequency, duration)) = pattern.pulses.front() {
+            if start > pattern.elapsed {
+                break;
+            }
+
+            match pattern.hand {
+                Some(hand) => pulse_with_frequency(hand, amplitude, frequency, duration),
+                None => {
+                    pulse_with_frequency(Hand::Left, amplitude, frequency, duration);
+                    pulse_with_frequency(Hand::Right, amplitude, frequency, duration);
+                }
+            }
+
+            pattern.pulses.pop_front();
+        }
+
+        pattern.elapsed += delta;
+        !pattern.pulses.is_empty()
+    });
+}
@@ -0,0 +1,67 @@
+use windows::Win32::UI::Input::XboxController::{XINPUT_GAMEPAD_BUTTON_FLAGS, XINPUT_STATE};
+
+/// Builds an XInput state, e.g. to override the state in `on_xinput_get_state`
+#[derive(Clone, Copy, Default)]
+pub struct XInputStateBuilder {
+    state: XINPUT_STATE,
+}
+
+impl XInputStateBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Starts from an existing state, keeping its packet number
+    pub fn from_state(state: XINPUT_STATE) -> Self {
+        Self { state }
+    }
+
+    /// Toggles the button flags in `btn`, see the `XINPUT_GAMEPAD_*` constants
+    pub fn button(mut self, btn: u16) -> Self {
+        self.state.Gamepad.wButtons.0 ^= btn;
+        self
+    }
+
+    /// Sets whether the button flags in `btn` are pressed
+    pub fn set_button(mut self, btn: XINPUT_GAMEPAD_BUTTON_FLAGS, pressed: bool) -> Self {
+        if pressed {
+            self.state.Gamepad.wButtons.0 |= btn.0;
+        } else {
+            self.state.Gamepad.wButtons.0 &= !btn.0;
+        }
+
+        self
+    }
+
+    pub fn left_trigger(mut self, v: u8) -> Self {
+        self.state.Gamepad.bLeftTrigger = v;
+        self
+    }
+
+    pub fn right_trigger(mut self, v: u8) -> Self {
+        self.state.Gamepad.bRightTrigger = v;
+        self
+    }
+
+    pub fn left_thumb(mut self, x: i16, y: i16) -> Self {
+        self.state.Gamepad.sThumbLX = x;
+        self.state.Gamepad.sThumbLY = y;
+        self
+    }
+
+    pub fn right_thumb(mut self, x: i16, y: i16) -> Self {
+        self.state.Gamepad.sThumbRX = x;
+        self.state.Gamepad.sThumbRY = y;
+        self
+    }
+
+    /// Sets the packet number, which the game uses to detect that the state has changed
+    pub fn packet_number(mut self, packet_number: u32) -> Self {
+        self.state.dwPacketNumber = packet_number;
+        self
+    }
+
+    pub fn build(self) -> XINPUT_STATE {
+        self.state
+    }
+}
@@ -0,0 +1,131 @@
+//! Conversions between the UEVR math types and [`glam`], enabled by the `glam` feature
+//!
+//! Values are converted as-is, no axes are swapped: UE uses a left-handed, Z-up space in centimeters
+//! (X forward, Y right), while the VR pose functions return the runtime's space in meters. glam
+//! doesn't assume a handedness for these types, so its math works the same in both.
+//!
+//! `UEVR_Matrix4x4f` is row-major and used with row vectors like UE's `FMatrix` (translation in the
+//! last row), glam's `Mat4` is column-major and used with column vectors. Both describe the same
+//! transform with the same memory layout, so `Mat4::transform_point3` matches `point * matrix` in UE.
+//!
+//! Rotators are converted using [`crate::util::math`].
+
+use glam::{Mat4, Quat, Vec2, Vec3};
+
+use crate::{
+    bindings::{UEVR_Matrix4x4f, UEVR_Quaternionf, UEVR_Rotatorf, UEVR_Vector2f, UEVR_Vector3f},
+    util::math::{quat_to_rotator, rotator_to_quat},
+};
+
+impl From<UEVR_Vector2f> for Vec2 {
+    fn from(value: UEVR_Vector2f) -> Self {
+        Vec2::new(value.x, value.y)
+    }
+}
+
+impl From<Vec2> for UEVR_Vector2f {
+    fn from(value: Vec2) -> Self {
+        UEVR_Vector2f {
+            x: value.x,
+            y: value.y,
+        }
+    }
+}
+
+impl From<UEVR_Vector3f> for Vec3 {
+    fn from(value: UEVR_Vector3f) -> Self {
+        Vec3::new(value.x, value.y, value.z)
+    }
+}
+
+impl From<Vec3> for UEVR_Vector3f {
+    fn from(value: Vec3) -> Self {
+        UEVR_Vector3f {
+            x: value.x,
+            y: value.y,
+            z: value.z,
+        }
+    }
+}
+
+impl From<UEVR_Quaternionf> for Quat {
+    fn from(value: UEVR_Quaternionf) -> Self {
+        Quat::from_xyzw(value.x, value.y, value.z, value.w)
+    }
+}
+
+impl From<Quat> for UEVR_Quaternionf {
+    fn from(value: Quat) -> Self {
+        UEVR_Quaternionf {
+            w: value.w,
+            x: value.x,
+            y: value.y,
+            z: value.z,
+        }
+    }
+}
+
+impl From<UEVR_Matrix4x4f> for Mat4 {
+    fn from(value: UEVR_Matrix4x4f) -> Self {
+        // The rows of a row-vector matrix are the columns of the equivalent column-vector matrix
+        Mat4::from_cols_array_2d(&value.m)
+    }
+}
+
+impl From<Mat4> for UEVR_Matrix4x4f {
+    fn from(value: Mat4) -> Self {
+        UEVR_Matrix4x4f {
+            m: value.to_cols_array_2d(),
+        }
+    }
+}
+
+impl From<UEVR_Rotatorf> for Quat {
+    fn from(value: UEVR_Rotatorf) -> Self {
+        rotator_to_quat(&value).into()
+    }
+}
+
+impl From<Quat> for UEVR_Rotatorf {
+    fn from(value: Quat) -> Self {
+        quat_to_rotator(&value.into())
+    }
+}
+
+#[cfg(all(test, feature = "glam"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mat4_round_trip() {
+        let matrix = UEVR_Matrix4x4f {
+            m: [
+                [0.0, 2.0, 0.0, 0.0],
+                [-3.0, 0.0, 0.0, 0.0],
+                [0.0, 0.0, 4.0, 0.0],
+                [5.0, 6.0, 7.0, 1.0],
+            ],
+        };
+
+        let converted = Mat4::from(matrix);
+        assert_eq!(UEVR_Matrix4x4f::from(converted).m, matrix.m);
+
+        // Same transform on both sides, the translation is in the last row of the UEVR matrix
+        let point = Vec3::new(1.0, 2.0, 3.0);
+        let expected = matrix.transform_point(point.into());
+        assert_eq!(converted.transform_point3(point), Vec3::from(expected));
+    }
+
+    #[test]
+    fn quat_round_trip() {
+        let quat = Quat::from_rotation_z(90f32.to_radians());
+        let converted = UEVR_Quaternionf::from(quat);
+
+        assert_eq!(Quat::from(converted), quat);
+        assert!((converted.z - std::f32::consts::FRAC_1_SQRT_2).abs() < 1e-6);
+
+        let rotator = UEVR_Rotatorf::from(quat);
+        assert!((rotator.yaw - 90.0).abs() < 1e-4);
+        assert!(Quat::from(rotator).abs_diff_eq(quat, 1e-6));
+    }
+}
@@ -1,3 +1,5 @@
+#![cfg_attr(feature = "allocator_api", feature(allocator_api))]
+
 #[cfg(not(windows))]
 compile_error!("This crate can only be built against Windows targets");
 
@@ -5,15 +7,65 @@ pub mod api;
 
 #[allow(warnings)]
 pub mod bindings;
+#[cfg(feature = "glam")]
+mod glam_impls;
 pub mod plugin;
 pub mod util;
 
+use std::ffi::{c_char, CStr};
+
 use bindings::{
     UEVR_PluginInitializeParam, UEVR_PluginVersion, UEVR_PLUGIN_VERSION_MAJOR,
     UEVR_PLUGIN_VERSION_MINOR, UEVR_PLUGIN_VERSION_PATCH,
 };
 
 pub use rusty_uevr_macros::define_object;
+#[cfg(feature = "registry")]
+pub use rusty_uevr_macros::static_class;
+pub use rusty_uevr_macros::Ptr;
+
+#[cfg(feature = "registry")]
+#[doc(hidden)]
+pub use linkme as __linkme;
+
+/// Plugin information exported by [`plugin_metadata!`], so it can be shown without initializing the plugin
+///
+/// This is not part of UEVR's plugin API, UEVR and its UI never read it. Every field points to a static,
+/// nul-terminated UTF-8 string.
+#[repr(C)]
+#[derive(Clone, Copy, Debug)]
+pub struct PluginMetadata {
+    pub name: *const c_char,
+    pub version: *const c_char,
+    pub author: *const c_char,
+    pub description: *const c_char,
+}
+
+unsafe impl Sync for PluginMetadata {}
+
+impl PluginMetadata {
+    pub const fn new(
+        name: &'static CStr,
+        version: &'static CStr,
+        author: &'static CStr,
+        description: &'static CStr,
+    ) -> Self {
+        Self {
+            name: name.as_ptr(),
+            version: version.as_ptr(),
+            author: author.as_ptr(),
+            description: description.as_ptr(),
+        }
+    }
+}
+
+#[doc(hidden)]
+pub const fn _metadata_cstr(bytes: &'static [u8]) -> &'static CStr {
+    match CStr::from_bytes_with_nul(bytes) {
+        Ok(value) => value,
+        Err(_) => panic!("plugin metadata can't contain nul characters"),
+    }
+}
 
 pub unsafe fn uevr_plugin_required_version(version: *mut UEVR_PluginVersion) {
     (*version).major = UEVR_PLUGIN_VERSION_MAJOR as _;
@@ -51,6 +103,21 @@ pub unsafe fn uevr_plugin_initialize(param: *const UEVR_PluginInitializeParam) -
     true
 }
 
+/// Calls [`plugin::Plugin::on_unload`] and removes all function hooks, the DLL is unloaded afterwards
+///
+/// # Safety
+///
+/// Must only be called once, from `DllMain` while the DLL is being detached, when no other thread can
+/// call into the plugin anymore.
+pub unsafe fn uevr_plugin_unload() {
+    if let Some(plugin) = plugin::global_plugin() {
+        plugin.on_unload();
+    }
+
+    // Hooked engine functions would jump into the unloaded DLL otherwise
+    api::fn_hooks::unregister_all();
+}
+
 #[macro_export]
 macro_rules! define_plugin {
     ($plugin:expr) => {
@@ -73,15 +140,54 @@ macro_rules! define_plugin {
         unsafe extern "system" fn DllMain(
             _dll_module: *mut std::ffi::c_void,
             call_reason: u32,
-            _reserved: *mut std::ffi::c_void,
+            reserved: *mut std::ffi::c_void,
         ) -> bool {
             if call_reason == 1 {
                 let plugin = $plugin;
                 plugin.on_dllmain();
                 $crate::plugin::_GLOBAL_PLUGIN = Some(Box::new(plugin));
+            } else if call_reason == 0 && reserved.is_null() {
+                // A non-null reserved pointer means the process is exiting, other threads may have been
+                // killed while holding locks, and the engine memory doesn't need to be restored anyway
+                $crate::uevr_plugin_unload();
             }
 
             true
         }
     };
 }
+
+/// Exports a `rusty_uevr_plugin_metadata` function returning the name, version, author and description of the plugin
+///
+/// This is a convention of this crate, not of UEVR: UEVR and its UI never call the export, so the metadata
+/// is only seen by tools that look for it, like plugin managers.
+///
+/// ```ignore
+/// rusty_uevr::plugin_metadata! {
+///     name: "MyMod",
+///     version: "1.2.3",
+///     author: "Alice",
+///     description: "Does VR things",
+/// }
+/// ```
+#[macro_export]
+macro_rules! plugin_metadata {
+    (
+        name: $name:literal,
+        version: $version:literal,
+        author: $author:literal,
+        description: $description:literal $(,)?
+    ) => {
+        #[no_mangle]
+        extern "C" fn rusty_uevr_plugin_metadata() -> *const $crate::PluginMetadata {
+            static METADATA: $crate::PluginMetadata = $crate::PluginMetadata::new(
+                $crate::_metadata_cstr(concat!($name, "\0").as_bytes()),
+                $crate::_metadata_cstr(concat!($version, "\0").as_bytes()),
+                $crate::_metadata_cstr(concat!($author, "\0").as_bytes()),
+                $crate::_metadata_cstr(concat!($description, "\0").as_bytes()),
+            );
+
+            &METADATA
+        }
+    };
+}
@@ -1,7 +1,14 @@
-use std::ffi::c_void;
+use std::{
+    collections::HashSet,
+    ffi::c_void,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        LazyLock, Mutex,
+    },
+};
 
 use windows::Win32::{
-    Foundation::HWND,
+    Foundation::{ERROR_SUCCESS, HWND},
     Graphics::{
         Direct3D11::{ID3D11DeviceContext, ID3D11RenderTargetView, ID3D11Texture2D},
         Direct3D12::{ID3D12GraphicsCommandList, ID3D12Resource, D3D12_CPU_DESCRIPTOR_HANDLE},
@@ -10,7 +17,11 @@ use windows::Win32::{
 };
 
 use super::{
-    api::{Ptr, UGameEngine},
+    api::{
+        advance_game_clock, canvas::Canvas, keyboard, object_hook, poll_cvar_watchers,
+        poll_property_watchers, run_game_thread_queue, vr, Ptr, StaticClass, UGameEngine, UObject,
+        UWorld,
+    },
     bindings::{
         UEVR_FCanvasHandle, UEVR_FSlateRHIRendererHandle, UEVR_FViewportHandle,
         UEVR_FViewportInfoHandle, UEVR_PluginCallbacks, UEVR_Rotatorf, UEVR_SDKCallbacks,
@@ -21,11 +32,33 @@ use super::{
 
 pub static mut _GLOBAL_PLUGIN: Option<Box<dyn Plugin>> = None;
 
+/// Returns the plugin registered using `define_plugin!`
+pub(crate) fn global_plugin() -> Option<&'static dyn Plugin> {
+    // Only written from DllMain while attaching, before anything else can read it
+    unsafe { (&raw const _GLOBAL_PLUGIN).as_ref() }.and_then(|plugin| plugin.as_deref())
+}
+
+/// Whether [`Plugin::on_vr_runtime_ready`] has been fired already
+static VR_RUNTIME_READY: AtomicBool = AtomicBool::new(false);
+
+/// Whether the game was paused during the previous engine tick
+static GAME_PAUSED: AtomicBool = AtomicBool::new(false);
+
+/// Whether the world creation callback used for [`Plugin::on_level_changed`] has been registered
+static WORLD_CALLBACK_REGISTERED: AtomicBool = AtomicBool::new(false);
+
+/// Worlds that [`Plugin::on_level_changed`] has already been fired for
+static SEEN_WORLDS: LazyLock<Mutex<HashSet<usize>>> = LazyLock::new(|| Mutex::new(HashSet::new()));
+
 #[allow(unused_variables)]
 pub trait Plugin {
     // Main plugin callbacks
     fn on_dllmain(&self) {}
     fn on_initialize(&self) {}
+    /// Called when the plugin DLL is unloaded, function hooks are removed afterwards
+    ///
+    /// Not called when the DLL is unloaded because the process is exiting.
+    fn on_unload(&self) {}
     fn on_present(&self) {}
     fn on_post_render_vr_framework_dx11(
         &self,
@@ -42,10 +75,19 @@ pub trait Plugin {
     ) {
     }
     fn on_device_reset(&self) {}
+    /// Called once, on the first engine tick where the VR runtime is ready
+    fn on_vr_runtime_ready(&self) {}
     fn on_message(&self, hwnd: HWND, msg: u32, wparam: u64, lparam: i64) -> bool {
         true
     }
-    fn on_xinput_get_state(&self, retval: &mut u32, user_index: u32, state: *mut XINPUT_STATE) {}
+    /// Called when the game reads the state of a controller, return `Some` to replace the state
+    fn on_xinput_get_state(
+        &self,
+        user_index: u32,
+        current_state: &XINPUT_STATE,
+    ) -> Option<XINPUT_STATE> {
+        None
+    }
     fn on_xinput_set_state(
         &self,
         retval: &mut u32,
@@ -56,7 +98,32 @@ pub trait Plugin {
 
     // Game/Engine callbacks
     fn on_pre_engine_tick(&self, engine: UGameEngine, delta: f32) {}
+    /// Return `true` to receive [`Plugin::on_level_changed`]
+    ///
+    /// New worlds are found by comparing the object array every engine tick, so this is off by default.
+    fn wants_level_events(&self) -> bool {
+        false
+    }
+    /// Called the first time each distinct world is seen, e.g. after a level has been loaded
+    ///
+    /// Only called if [`Plugin::wants_level_events`] returns `true`.
+    fn on_level_changed(&self, world: UWorld) {}
+    /// Called when the game gets paused, checked before every engine tick
+    fn on_game_paused(&self) {}
+    /// Called when the game is no longer paused
+    fn on_game_resumed(&self) {}
     fn on_post_engine_tick(&self, engine: UGameEngine, delta: f32) {}
+    /// Called after every engine tick, with the world that is currently being played
+    ///
+    /// Calls [`Plugin::on_post_engine_tick`] by default, only one of them has to be implemented.
+    fn on_post_engine_tick_with_world(
+        &self,
+        engine: UGameEngine,
+        world: Option<UWorld>,
+        delta: f32,
+    ) {
+        self.on_post_engine_tick(engine, delta);
+    }
     fn on_pre_slate_draw_window(
         &self,
         renderer: UEVR_FSlateRHIRendererHandle,
@@ -93,14 +160,14 @@ pub trait Plugin {
         &self,
         viewport_client: UEVR_UGameViewportClientHandle,
         viewport: UEVR_FViewportHandle,
-        canvas: UEVR_FCanvasHandle,
+        canvas: Canvas,
     ) {
     }
     fn on_post_viewport_client_draw(
         &self,
         viewport_client: UEVR_UGameViewportClientHandle,
         viewport: UEVR_FViewportHandle,
-        canvas: UEVR_FCanvasHandle,
+        canvas: Canvas,
     ) {
     }
 }
@@ -183,6 +250,8 @@ unsafe extern "C" fn on_post_render_vr_framework_dx12(
 }
 
 unsafe extern "C" fn on_message(hwnd: *mut c_void, msg: u32, wparam: u64, lparam: i64) -> bool {
+    keyboard::handle_message(msg, wparam);
+
     if let Some(plugin) = _GLOBAL_PLUGIN.as_ref() {
         return plugin.on_message(HWND(hwnd), msg, wparam, lparam);
     }
@@ -191,12 +260,19 @@ unsafe extern "C" fn on_message(hwnd: *mut c_void, msg: u32, wparam: u64, lparam
 }
 
 unsafe extern "C" fn on_xinput_get_state(retval: *mut u32, user_index: u32, state: *mut c_void) {
+    let Some(state) = (state as *mut XINPUT_STATE).as_mut() else {
+        return;
+    };
+
     if let Some(plugin) = _GLOBAL_PLUGIN.as_ref() {
-        plugin.on_xinput_get_state(
-            retval.as_mut().unwrap(),
-            user_index,
-            state as *mut XINPUT_STATE,
-        );
+        if let Some(new_state) = plugin.on_xinput_get_state(user_index, state) {
+            *state = new_state;
+
+            // The controller might not be connected, but the game should still see the new state
+            if let Some(retval) = retval.as_mut() {
+                *retval = ERROR_SUCCESS.0;
+            }
+        }
     }
 }
 
@@ -215,14 +291,87 @@ unsafe extern "C" fn on_xinput_set_state(
 }
 
 unsafe extern "C" fn on_pre_engine_tick(engine: UEVR_UGameEngineHandle, delta: f32) {
+    let engine = UGameEngine::from_ptr(engine as *mut c_void);
+
+    keyboard::snapshot_transitions();
+    advance_game_clock(delta);
+    register_world_callback(engine);
+    object_hook::poll_object_events();
+    poll_cvar_watchers();
+    poll_property_watchers();
+    run_game_thread_queue();
+    vr::haptics::tick(delta);
+
     if let Some(plugin) = _GLOBAL_PLUGIN.as_ref() {
-        plugin.on_pre_engine_tick(UGameEngine::from_ptr(engine as *mut c_void), delta);
+        if !VR_RUNTIME_READY.load(Ordering::Relaxed) && vr::is_runtime_ready() {
+            VR_RUNTIME_READY.store(true, Ordering::Relaxed);
+            plugin.on_vr_runtime_ready();
+        }
+
+        let paused = engine
+            .get_world()
+            .and_then(|world| world.is_paused())
+            .unwrap_or(false);
+
+        if GAME_PAUSED.swap(paused, Ordering::Relaxed) != paused {
+            if paused {
+                plugin.on_game_paused();
+            } else {
+                plugin.on_game_resumed();
+            }
+        }
+
+        plugin.on_pre_engine_tick(engine, delta);
+    }
+}
+
+/// Starts watching for new worlds if the plugin wants level events, reporting the current world right away
+fn register_world_callback(engine: UGameEngine) {
+    if WORLD_CALLBACK_REGISTERED.load(Ordering::Relaxed) {
+        return;
+    }
+
+    if !global_plugin().is_some_and(|plugin| plugin.wants_level_events()) {
+        return;
+    }
+
+    let Some(class) = UWorld::static_class_safe() else {
+        return;
+    };
+
+    WORLD_CALLBACK_REGISTERED.store(true, Ordering::Relaxed);
+    object_hook::on_class_create(class, on_world_created);
+    object_hook::on_class_destroy(class, on_world_destroyed);
+
+    // Looking up the existing worlds through UObjectHook would activate it, so only report the current one
+    if let Some(world) = engine.get_world() {
+        on_world_created(UObject::from_ptr(world.to_ptr()));
+    }
+}
+
+/// Forgets destroyed worlds, since a new world can be allocated at the same address
+fn on_world_destroyed(world: UObject) {
+    SEEN_WORLDS
+        .lock()
+        .unwrap()
+        .remove(&(world.to_ptr() as usize));
+}
+
+fn on_world_created(world: UObject) {
+    if !SEEN_WORLDS.lock().unwrap().insert(world.to_ptr() as usize) {
+        return;
+    }
+
+    if let Some(plugin) = global_plugin() {
+        plugin.on_level_changed(UWorld::from_ptr(world.to_ptr()));
     }
 }
 
 unsafe extern "C" fn on_post_engine_tick(engine: UEVR_UGameEngineHandle, delta: f32) {
     if let Some(plugin) = _GLOBAL_PLUGIN.as_ref() {
-        plugin.on_post_engine_tick(UGameEngine::from_ptr(engine as *mut c_void), delta);
+        let engine = UGameEngine::from_ptr(engine as *mut c_void);
+
+        plugin.on_post_engine_tick_with_world(engine, engine.get_world(), delta);
     }
 }
 
@@ -290,7 +439,7 @@ unsafe extern "C" fn on_pre_viewport_client_draw(
     canvas: UEVR_FCanvasHandle,
 ) {
     if let Some(plugin) = _GLOBAL_PLUGIN.as_ref() {
-        plugin.on_pre_viewport_client_draw(viewport_client, viewport, canvas);
+        plugin.on_pre_viewport_client_draw(viewport_client, viewport, Canvas::new(canvas));
     }
 }
 
@@ -300,6 +449,6 @@ unsafe extern "C" fn on_post_viewport_client_draw(
     canvas: UEVR_FCanvasHandle,
 ) {
     if let Some(plugin) = _GLOBAL_PLUGIN.as_ref() {
-        plugin.on_post_viewport_client_draw(viewport_client, viewport, canvas);
+        plugin.on_post_viewport_client_draw(viewport_client, viewport, Canvas::new(canvas));
     }
 }
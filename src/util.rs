@@ -1,3 +1,5 @@
+pub mod math;
+
 use std::ffi::CString;
 
 /// Simple helper function that converts a string into a CString
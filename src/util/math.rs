@@ -0,0 +1,609 @@
+//! Math on the UEVR binding types, without any dependencies
+//!
+//! Rotators are converted like `FRotator::Quaternion` and `FQuat::Rotator`. They're in degrees, roll
+//! is applied around X first, then pitch around Y, then yaw around Z.
+
+use std::{
+    error::Error,
+    fmt,
+    ops::{Add, AddAssign, Div, Mul, Neg, Sub, SubAssign},
+};
+
+use crate::bindings::{
+    UEVR_Matrix4x4f, UEVR_Quaternionf, UEVR_Rotatorf, UEVR_Vector2f, UEVR_Vector3f, UEVR_Vector4f,
+};
+
+/// Implements the component-wise operators and the methods shared by all vector types
+macro_rules! impl_vector {
+    ($ty:ident, $($field:ident),+) => {
+        impl $ty {
+            pub const ZERO: $ty = $ty { $($field: 0.0),+ };
+
+            pub const fn new($($field: f32),+) -> Self {
+                Self { $($field),+ }
+            }
+
+            pub fn dot(&self, other: &$ty) -> f32 {
+                0.0 $(+ self.$field * other.$field)+
+            }
+
+            pub fn length_squared(&self) -> f32 {
+                self.dot(self)
+            }
+
+            pub fn length(&self) -> f32 {
+                self.length_squared().sqrt()
+            }
+
+            /// Returns the vector scaled to a length of 1, or `None` if it's (close to) zero
+            pub fn normalize(&self) -> Option<$ty> {
+                let length = self.length();
+
+                (length > f32::EPSILON && length.is_finite()).then(|| *self / length)
+            }
+
+            /// Same as [`Self::normalize`], but returns a zero vector instead of `None`
+            pub fn normalize_or_zero(&self) -> $ty {
+                self.normalize().unwrap_or(Self::ZERO)
+            }
+        }
+
+        impl Add for $ty {
+            type Output = $ty;
+
+            fn add(self, rhs: $ty) -> $ty {
+                $ty { $($field: self.$field + rhs.$field),+ }
+            }
+        }
+
+        impl AddAssign for $ty {
+            fn add_assign(&mut self, rhs: $ty) {
+                *self = *self + rhs;
+            }
+        }
+
+        impl Sub for $ty {
+            type Output = $ty;
+
+            fn sub(self, rhs: $ty) -> $ty {
+                $ty { $($field: self.$field - rhs.$field),+ }
+            }
+        }
+
+        impl SubAssign for $ty {
+            fn sub_assign(&mut self, rhs: $ty) {
+                *self = *self - rhs;
+            }
+        }
+
+        impl Neg for $ty {
+            type Output = $ty;
+
+            fn neg(self) -> $ty {
+                $ty { $($field: -self.$field),+ }
+            }
+        }
+
+        impl Mul<f32> for $ty {
+            type Output = $ty;
+
+            fn mul(self, rhs: f32) -> $ty {
+                $ty { $($field: self.$field * rhs),+ }
+            }
+        }
+
+        impl Div<f32> for $ty {
+            type Output = $ty;
+
+            fn div(self, rhs: f32) -> $ty {
+                $ty { $($field: self.$field / rhs),+ }
+            }
+        }
+    };
+}
+
+impl_vector!(UEVR_Vector2f, x, y);
+impl_vector!(UEVR_Vector3f, x, y, z);
+
+impl UEVR_Vector3f {
+    pub fn cross(&self, other: &UEVR_Vector3f) -> UEVR_Vector3f {
+        UEVR_Vector3f {
+            x: self.y * other.z - self.z * other.y,
+            y: self.z * other.x - self.x * other.z,
+            z: self.x * other.y - self.y * other.x,
+        }
+    }
+}
+
+impl UEVR_Quaternionf {
+    pub fn dot(&self, other: &UEVR_Quaternionf) -> f32 {
+        self.w * other.w + self.x * other.x + self.y * other.y + self.z * other.z
+    }
+
+    /// Returns the inverse rotation, assuming the quaternion is normalized
+    pub fn conjugate(&self) -> UEVR_Quaternionf {
+        UEVR_Quaternionf {
+            w: self.w,
+            x: -self.x,
+            y: -self.y,
+            z: -self.z,
+        }
+    }
+
+    /// Returns `self * other`, which rotates by `other` first and by `self` second
+    pub fn multiply(&self, other: &UEVR_Quaternionf) -> UEVR_Quaternionf {
+        let (a, b) = (self, other);
+
+        UEVR_Quaternionf {
+            x: a.w * b.x + a.x * b.w + a.y * b.z - a.z * b.y,
+            y: a.w * b.y - a.x * b.z + a.y * b.w + a.z * b.x,
+            z: a.w * b.z + a.x * b.y - a.y * b.x + a.z * b.w,
+            w: a.w * b.w - a.x * b.x - a.y * b.y - a.z * b.z,
+        }
+    }
+
+    pub fn rotate_vector(&self, vector: UEVR_Vector3f) -> UEVR_Vector3f {
+        let axis = UEVR_Vector3f::new(self.x, self.y, self.z);
+        let t = axis.cross(&vector) * 2.0;
+
+        vector + t * self.w + axis.cross(&t)
+    }
+
+    /// Shortest rotation from one unit vector to another
+    ///
+    /// Opposite vectors are turned around the Y axis, the vertical axis of the VR space.
+    pub fn rotation_between(from: UEVR_Vector3f, to: UEVR_Vector3f) -> UEVR_Quaternionf {
+        let d = from.dot(&to);
+
+        if d < -0.9999 {
+            return UEVR_Quaternionf {
+                w: 0.0,
+                x: 0.0,
+                y: 1.0,
+                z: 0.0,
+            };
+        }
+
+        let axis = from.cross(&to);
+        let w = 1.0 + d;
+        let length = (w * w + axis.length_squared()).sqrt();
+
+        UEVR_Quaternionf {
+            w: w / length,
+            x: axis.x / length,
+            y: axis.y / length,
+            z: axis.z / length,
+        }
+    }
+}
+
+/// Converts a rotator into a quaternion, e.g. a yaw of 90 becomes `(x: 0, y: 0, z: 0.707, w: 0.707)`
+pub fn rotator_to_quat(rotator: &UEVR_Rotatorf) -> UEVR_Quaternionf {
+    let (sp, cp) = (rotator.pitch.to_radians() * 0.5).sin_cos();
+    let (sy, cy) = (rotator.yaw.to_radians() * 0.5).sin_cos();
+    let (sr, cr) = (rotator.roll.to_radians() * 0.5).sin_cos();
+
+    UEVR_Quaternionf {
+        x: cr * sp * sy - sr * cp * cy,
+        y: -cr * sp * cy - sr * cp * sy,
+        z: cr * cp * sy - sr * sp * cy,
+        w: cr * cp * cy + sr * sp * sy,
+    }
+}
+
+/// Converts a unit quaternion into a rotator with every axis within `(-180, 180]`
+pub fn quat_to_rotator(quat: &UEVR_Quaternionf) -> UEVR_Rotatorf {
+    // Same threshold as UE, close to straight up or down yaw and roll can't be told apart
+    const SINGULARITY_THRESHOLD: f32 = 0.4999995;
+
+    let UEVR_Quaternionf { w, x, y, z } = *quat;
+    let singularity_test = z * x - w * y;
+    let yaw = (2.0 * (w * z + x * y))
+        .atan2(1.0 - 2.0 * (y * y + z * z))
+        .to_degrees();
+
+    if singularity_test < -SINGULARITY_THRESHOLD {
+        UEVR_Rotatorf {
+            pitch: -90.0,
+            yaw,
+            roll: normalize_axis(-yaw - 2.0 * x.atan2(w).to_degrees()),
+        }
+    } else if singularity_test > SINGULARITY_THRESHOLD {
+        UEVR_Rotatorf {
+            pitch: 90.0,
+            yaw,
+            roll: normalize_axis(yaw - 2.0 * x.atan2(w).to_degrees()),
+        }
+    } else {
+        UEVR_Rotatorf {
+            pitch: (2.0 * singularity_test).asin().to_degrees(),
+            yaw,
+            roll: (-2.0 * (w * x + y * z))
+                .atan2(1.0 - 2.0 * (x * x + y * y))
+                .to_degrees(),
+        }
+    }
+}
+
+/// Wraps an angle in degrees into `(-180, 180]`, like `FRotator::NormalizeAxis`
+pub fn normalize_axis(angle: f32) -> f32 {
+    let angle = angle.rem_euclid(360.0);
+
+    if angle > 180.0 {
+        angle - 360.0
+    } else {
+        angle
+    }
+}
+
+impl UEVR_Rotatorf {
+    /// Returns the rotator with every axis wrapped into `(-180, 180]`
+    pub fn normalize(&self) -> UEVR_Rotatorf {
+        UEVR_Rotatorf {
+            pitch: normalize_axis(self.pitch),
+            yaw: normalize_axis(self.yaw),
+            roll: normalize_axis(self.roll),
+        }
+    }
+}
+
+/// Returned by [`UEVR_Matrix4x4f::inverse`] for matrices that can't be inverted
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SingularMatrixError;
+
+impl fmt::Display for SingularMatrixError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "the matrix is singular and can't be inverted")
+    }
+}
+
+impl Error for SingularMatrixError {}
+
+/// UEVR matrices are stored row-major and used with row vectors, like UE's `FMatrix`
+///
+/// A point is transformed as `point * matrix`, the first three rows hold the (scaled) X, Y and Z axes
+/// and the last row holds the translation. This is the layout [`crate::api::vr::Pose::to_matrix`] returns.
+impl UEVR_Matrix4x4f {
+    pub const IDENTITY: UEVR_Matrix4x4f = UEVR_Matrix4x4f {
+        m: [
+            [1.0, 0.0, 0.0, 0.0],
+            [0.0, 1.0, 0.0, 0.0],
+            [0.0, 0.0, 1.0, 0.0],
+            [0.0, 0.0, 0.0, 1.0],
+        ],
+    };
+
+    /// Returns `self * other`, which transforms by `self` first and by `other` second
+    pub fn multiply(&self, other: &UEVR_Matrix4x4f) -> UEVR_Matrix4x4f {
+        let mut result = [[0.0; 4]; 4];
+
+        for (row, result_row) in result.iter_mut().enumerate() {
+            for (column, value) in result_row.iter_mut().enumerate() {
+                *value = (0..4).map(|i| self.m[row][i] * other.m[i][column]).sum();
+            }
+        }
+
+        UEVR_Matrix4x4f { m: result }
+    }
+
+    /// Inverts the matrix, assuming it's affine (the last column is `0, 0, 0, 1`)
+    pub fn inverse(&self) -> Result<UEVR_Matrix4x4f, SingularMatrixError> {
+        let m = &self.m;
+
+        let cofactors = [
+            [
+                m[1][1] * m[2][2] - m[1][2] * m[2][1],
+                m[0][2] * m[2][1] - m[0][1] * m[2][2],
+                m[0][1] * m[1][2] - m[0][2] * m[1][1],
+            ],
+            [
+                m[1][2] * m[2][0] - m[1][0] * m[2][2],
+                m[0][0] * m[2][2] - m[0][2] * m[2][0],
+                m[0][2] * m[1][0] - m[0][0] * m[1][2],
+            ],
+            [
+                m[1][0] * m[2][1] - m[1][1] * m[2][0],
+                m[0][1] * m[2][0] - m[0][0] * m[2][1],
+                m[0][0] * m[1][1] - m[0][1] * m[1][0],
+            ],
+        ];
+
+        let determinant =
+            m[0][0] * cofactors[0][0] + m[0][1] * cofactors[1][0] + m[0][2] * cofactors[2][0];
+
+        if determinant.abs() <= f32::EPSILON || !determinant.is_finite() {
+            return Err(SingularMatrixError);
+        }
+
+        let mut result = Self::IDENTITY;
+
+        for (result_row, cofactor_row) in result.m.iter_mut().zip(cofactors) {
+            for (value, cofactor) in result_row.iter_mut().zip(cofactor_row) {
+                *value = cofactor / determinant;
+            }
+        }
+
+        let translation = [m[3][0], m[3][1], m[3][2]];
+
+        for column in 0..3 {
+            result.m[3][column] = -(0..3)
+                .map(|i| translation[i] * result.m[i][column])
+                .sum::<f32>();
+        }
+
+        Ok(result)
+    }
+
+    /// Transforms a point, applying the rotation, scale and translation
+    pub fn transform_point(&self, point: UEVR_Vector3f) -> UEVR_Vector3f {
+        self.transform_vector(point) + self.translation()
+    }
+
+    /// Transforms a homogeneous row vector, as needed for projection matrices
+    pub fn transform_vector4(&self, vector: UEVR_Vector4f) -> UEVR_Vector4f {
+        let v = [vector.x, vector.y, vector.z, vector.w];
+        let [x, y, z, w] =
+            [0, 1, 2, 3].map(|column| (0..4).map(|row| v[row] * self.m[row][column]).sum());
+
+        UEVR_Vector4f { x, y, z, w }
+    }
+
+    /// Transforms a direction, applying only the rotation and scale
+    pub fn transform_vector(&self, vector: UEVR_Vector3f) -> UEVR_Vector3f {
+        let m = &self.m;
+
+        UEVR_Vector3f {
+            x: vector.x * m[0][0] + vector.y * m[1][0] + vector.z * m[2][0],
+            y: vector.x * m[0][1] + vector.y * m[1][1] + vector.z * m[2][1],
+            z: vector.x * m[0][2] + vector.y * m[1][2] + vector.z * m[2][2],
+        }
+    }
+
+    pub fn translation(&self) -> UEVR_Vector3f {
+        UEVR_Vector3f::new(self.m[3][0], self.m[3][1], self.m[3][2])
+    }
+
+    /// Splits the matrix into its translation, rotation and scale
+    ///
+    /// Shear is not supported, a mirrored matrix is returned with a negative X scale.
+    pub fn decompose(&self) -> (UEVR_Vector3f, UEVR_Quaternionf, UEVR_Vector3f) {
+        let axes =
+            [0, 1, 2].map(|row| UEVR_Vector3f::new(self.m[row][0], self.m[row][1], self.m[row][2]));
+        let mut scale = UEVR_Vector3f::new(axes[0].length(), axes[1].length(), axes[2].length());
+
+        if axes[0].cross(&axes[1]).dot(&axes[2]) < 0.0 {
+            scale.x = -scale.x;
+        }
+
+        let [x, y, z] =
+            [(axes[0], scale.x), (axes[1], scale.y), (axes[2], scale.z)].map(|(axis, scale)| {
+                if scale.abs() > f32::EPSILON {
+                    axis / scale
+                } else {
+                    UEVR_Vector3f::ZERO
+                }
+            });
+
+        let rotation = rotation_from_axes([[x.x, x.y, x.z], [y.x, y.y, y.z], [z.x, z.y, z.z]]);
+
+        (self.translation(), rotation, scale)
+    }
+}
+
+/// Converts a rotation matrix with the rotated X, Y and Z axes as rows into a quaternion
+fn rotation_from_axes(m: [[f32; 3]; 3]) -> UEVR_Quaternionf {
+    let trace = m[0][0] + m[1][1] + m[2][2];
+
+    let (w, x, y, z) = if trace > 0.0 {
+        let s = (trace + 1.0).sqrt() * 2.0;
+
+        (
+            0.25 * s,
+            (m[1][2] - m[2][1]) / s,
+            (m[2][0] - m[0][2]) / s,
+            (m[0][1] - m[1][0]) / s,
+        )
+    } else if m[0][0] > m[1][1] && m[0][0] > m[2][2] {
+        let s = (1.0 + m[0][0] - m[1][1] - m[2][2]).sqrt() * 2.0;
+
+        (
+            (m[1][2] - m[2][1]) / s,
+            0.25 * s,
+            (m[0][1] + m[1][0]) / s,
+            (m[0][2] + m[2][0]) / s,
+        )
+    } else if m[1][1] > m[2][2] {
+        let s = (1.0 + m[1][1] - m[0][0] - m[2][2]).sqrt() * 2.0;
+
+        (
+            (m[2][0] - m[0][2]) / s,
+            (m[0][1] + m[1][0]) / s,
+            0.25 * s,
+            (m[1][2] + m[2][1]) / s,
+        )
+    } else {
+        let s = (1.0 + m[2][2] - m[0][0] - m[1][1]).sqrt() * 2.0;
+
+        (
+            (m[0][1] - m[1][0]) / s,
+            (m[0][2] + m[2][0]) / s,
+            (m[1][2] + m[2][1]) / s,
+            0.25 * s,
+        )
+    };
+
+    UEVR_Quaternionf { w, x, y, z }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_matrix_close(a: &UEVR_Matrix4x4f, b: &UEVR_Matrix4x4f) {
+        let close = a.m.iter().flatten().zip(b.m.iter().flatten());
+
+        assert!(
+            close.into_iter().all(|(a, b)| (a - b).abs() < 1e-5),
+            "{a:?} != {b:?}"
+        );
+    }
+
+    fn assert_close(a: UEVR_Vector3f, b: UEVR_Vector3f) {
+        assert!((a - b).length() < 1e-5, "{a:?} != {b:?}");
+    }
+
+    /// Yawed by 90 degrees, scaled by 2, 3 and 4 and moved to 5, 6, 7
+    fn transform() -> UEVR_Matrix4x4f {
+        UEVR_Matrix4x4f {
+            m: [
+                [0.0, 2.0, 0.0, 0.0],
+                [-3.0, 0.0, 0.0, 0.0],
+                [0.0, 0.0, 4.0, 0.0],
+                [5.0, 6.0, 7.0, 1.0],
+            ],
+        }
+    }
+
+    #[test]
+    fn matrix_multiply_applies_self_first() {
+        let translate = UEVR_Matrix4x4f {
+            m: [
+                [1.0, 0.0, 0.0, 0.0],
+                [0.0, 1.0, 0.0, 0.0],
+                [0.0, 0.0, 1.0, 0.0],
+                [1.0, 0.0, 0.0, 1.0],
+            ],
+        };
+        let scale = UEVR_Matrix4x4f {
+            m: [
+                [2.0, 0.0, 0.0, 0.0],
+                [0.0, 2.0, 0.0, 0.0],
+                [0.0, 0.0, 2.0, 0.0],
+                [0.0, 0.0, 0.0, 1.0],
+            ],
+        };
+
+        let point = translate
+            .multiply(&scale)
+            .transform_point(UEVR_Vector3f::ZERO);
+        assert_close(point, UEVR_Vector3f::new(2.0, 0.0, 0.0));
+
+        assert_matrix_close(
+            &transform().multiply(&UEVR_Matrix4x4f::IDENTITY),
+            &transform(),
+        );
+    }
+
+    #[test]
+    fn matrix_inverse_undoes_matrix() {
+        let inverse = transform().inverse().unwrap();
+
+        assert_matrix_close(&transform().multiply(&inverse), &UEVR_Matrix4x4f::IDENTITY);
+        assert_matrix_close(&inverse.multiply(&transform()), &UEVR_Matrix4x4f::IDENTITY);
+    }
+
+    #[test]
+    fn singular_matrix_has_no_inverse() {
+        let mut flat = transform();
+        flat.m[2] = [0.0; 4];
+
+        assert!(matches!(flat.inverse(), Err(SingularMatrixError)));
+    }
+
+    #[test]
+    fn matrix_decompose() {
+        let (translation, rotation, scale) = transform().decompose();
+        let half = std::f32::consts::FRAC_1_SQRT_2;
+
+        assert_close(translation, UEVR_Vector3f::new(5.0, 6.0, 7.0));
+        assert_close(scale, UEVR_Vector3f::new(2.0, 3.0, 4.0));
+        assert!((rotation.w - half).abs() < 1e-5 && (rotation.z - half).abs() < 1e-5);
+        assert!(rotation.x.abs() < 1e-5 && rotation.y.abs() < 1e-5);
+    }
+
+    #[test]
+    fn mirrored_matrix_decomposes_to_negative_x_scale() {
+        let mut mirrored = transform();
+        mirrored.m[0] = [0.0, -2.0, 0.0, 0.0];
+
+        let (_, _, scale) = mirrored.decompose();
+        assert_close(scale, UEVR_Vector3f::new(-2.0, 3.0, 4.0));
+    }
+
+    /// Whether both quaternions describe the same rotation, `q` and `-q` do
+    fn same_rotation(a: &UEVR_Quaternionf, b: &UEVR_Quaternionf) -> bool {
+        a.dot(b).abs() > 1.0 - 1e-5
+    }
+
+    fn rotator(pitch: f32, yaw: f32, roll: f32) -> UEVR_Rotatorf {
+        UEVR_Rotatorf { pitch, yaw, roll }
+    }
+
+    #[test]
+    fn yaw_to_quat() {
+        let quat = rotator_to_quat(&rotator(0.0, 90.0, 0.0));
+        let half = std::f32::consts::FRAC_1_SQRT_2;
+
+        assert!(quat.x.abs() < 1e-6 && quat.y.abs() < 1e-6);
+        assert!((quat.z - half).abs() < 1e-6 && (quat.w - half).abs() < 1e-6);
+    }
+
+    #[test]
+    fn rotator_round_trip() {
+        for (pitch, yaw, roll) in [(30.0, -120.0, 45.0), (-60.0, 170.0, -10.0), (0.0, 0.0, 0.0)] {
+            let result = quat_to_rotator(&rotator_to_quat(&rotator(pitch, yaw, roll)));
+
+            assert!((result.pitch - pitch).abs() < 1e-3, "{result:?}");
+            assert!((result.yaw - yaw).abs() < 1e-3, "{result:?}");
+            assert!((result.roll - roll).abs() < 1e-3, "{result:?}");
+        }
+    }
+
+    #[test]
+    fn rotator_singularities() {
+        for pitch in [90.0, -90.0] {
+            let quat = rotator_to_quat(&rotator(pitch, 40.0, 20.0));
+            let result = quat_to_rotator(&quat);
+
+            // Yaw and roll can't be told apart, but the rotation has to stay the same
+            assert_eq!(result.pitch, pitch);
+            assert!(
+                same_rotation(&rotator_to_quat(&result), &quat),
+                "{result:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn quat_multiply_applies_other_first() {
+        let yaw = rotator_to_quat(&rotator(0.0, 90.0, 0.0));
+        let pitch = rotator_to_quat(&rotator(90.0, 0.0, 0.0));
+        let x = UEVR_Vector3f::new(1.0, 0.0, 0.0);
+
+        assert_close(
+            yaw.multiply(&pitch).rotate_vector(x),
+            yaw.rotate_vector(pitch.rotate_vector(x)),
+        );
+        assert_close(yaw.rotate_vector(x), UEVR_Vector3f::new(0.0, 1.0, 0.0));
+        assert_close(yaw.conjugate().rotate_vector(yaw.rotate_vector(x)), x);
+    }
+
+    #[test]
+    fn rotation_between_vectors() {
+        let from = UEVR_Vector3f::new(0.0, 0.0, -1.0);
+
+        for to in [UEVR_Vector3f::new(1.0, 0.0, 0.0), from, -from] {
+            assert_close(
+                UEVR_Quaternionf::rotation_between(from, to).rotate_vector(from),
+                to,
+            );
+        }
+    }
+
+    #[test]
+    fn normalize_axis_wraps() {
+        assert_eq!(normalize_axis(190.0), -170.0);
+        assert_eq!(normalize_axis(-180.0), 180.0);
+        assert_eq!(normalize_axis(720.0), 0.0);
+    }
+}